@@ -0,0 +1,255 @@
+use core::fmt;
+use core::sync::atomic::{AtomicU64, Ordering};
+
+use int_sentinel::IntSentinel;
+
+/// An `Option<u64>` that can be shared between threads, stored in an `AtomicU64` using `u64::MAX` as a sentinel.
+///
+/// Every operation that stores a value checks it before touching the atomic, so that no thread can ever store the
+/// sentinel as a `Some`: such operations panic instead.
+///
+/// # Examples
+///
+/// ```rust
+/// # use sentinel_int::atomic::AtomicIntSentinel;
+/// use std::sync::atomic::Ordering;
+/// let slot = AtomicIntSentinel::new(None);
+/// // Worker 3 claims the slot.
+/// assert_eq!(slot.set_if_none(3, Ordering::AcqRel, Ordering::Acquire), Ok(()));
+/// // Worker 5 fails to claim it, and learns who owns it.
+/// assert_eq!(slot.set_if_none(5, Ordering::AcqRel, Ordering::Acquire), Err(3));
+/// // Worker 3 releases it.
+/// assert_eq!(slot.take(Ordering::AcqRel), Some(3));
+/// ```
+pub struct AtomicIntSentinel {
+    value: AtomicU64,
+}
+
+fn to_raw(option: Option<u64>) -> u64 {
+    let sentinel = IntSentinel::from(option);
+    unsafe { sentinel.to_u64_unchecked() }
+}
+
+fn from_raw(raw: u64) -> Option<u64> {
+    unsafe { IntSentinel::unchecked_new(raw) }.to_option()
+}
+
+impl AtomicIntSentinel {
+    /// Constructs a new `AtomicIntSentinel` containing `option`.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `option` is `Some(IntSentinel::sentinel())`.
+    pub fn new(option: Option<u64>) -> Self {
+        AtomicIntSentinel { value: AtomicU64::new(to_raw(option)) }
+    }
+
+    /// Constructs a new `AtomicIntSentinel` containing `None`.
+    pub const fn new_none() -> Self {
+        AtomicIntSentinel { value: AtomicU64::new(IntSentinel::SENTINEL) }
+    }
+
+    /// Loads the contained value.
+    pub fn load(&self, order: Ordering) -> Option<u64> {
+        from_raw(self.value.load(order))
+    }
+
+    /// Stores `option`.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `option` is `Some(IntSentinel::sentinel())`.
+    pub fn store(&self, option: Option<u64>, order: Ordering) {
+        self.value.store(to_raw(option), order)
+    }
+
+    /// Stores `option`, returning the previous value.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `option` is `Some(IntSentinel::sentinel())`.
+    pub fn swap(&self, option: Option<u64>, order: Ordering) -> Option<u64> {
+        from_raw(self.value.swap(to_raw(option), order))
+    }
+
+    /// Takes the contained value, leaving `None` in its place.
+    pub fn take(&self, order: Ordering) -> Option<u64> {
+        self.swap(None, order)
+    }
+
+    /// Stores `new` if the contained value is `current`.
+    ///
+    /// Returns `Ok` with the previous value on success, `Err` with the contained value on failure.
+    /// See `AtomicU64::compare_exchange` for the meaning of the orderings.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `current` or `new` is `Some(IntSentinel::sentinel())`.
+    pub fn compare_exchange(
+        &self,
+        current: Option<u64>,
+        new: Option<u64>,
+        success: Ordering,
+        failure: Ordering,
+    ) -> Result<Option<u64>, Option<u64>> {
+        self.value
+            .compare_exchange(to_raw(current), to_raw(new), success, failure)
+            .map(from_raw)
+            .map_err(from_raw)
+    }
+
+    /// Stores `value` if the contained value is `None`, i.e. claims the slot.
+    ///
+    /// Returns `Ok` if the slot was claimed, `Err` with the contained value otherwise.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `value` is `IntSentinel::sentinel()`.
+    pub fn set_if_none(&self, value: u64, success: Ordering, failure: Ordering) -> Result<(), u64> {
+        match self.compare_exchange(None, Some(value), success, failure) {
+            Ok(_) => Ok(()),
+            Err(current) => Err(current.expect("compare_exchange with `None` failed on a `None` value")),
+        }
+    }
+
+    /// Fetches the contained value and applies `f` to it, storing the result if `f` returns `Some`.
+    ///
+    /// Returns `Ok` with the previous value if `f` returned `Some`, `Err` with the contained value otherwise.
+    /// See `AtomicU64::fetch_update` for the meaning of the orderings.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `f` returns `Some(Some(IntSentinel::sentinel()))`, without storing it.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use sentinel_int::atomic::AtomicIntSentinel;
+    /// use std::sync::atomic::Ordering;
+    /// let counter = AtomicIntSentinel::new(Some(41));
+    /// let previous = counter.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |x| Some(x.map(|x| x + 1)));
+    /// assert_eq!(previous, Ok(Some(41)));
+    /// assert_eq!(counter.load(Ordering::SeqCst), Some(42));
+    /// ```
+    pub fn fetch_update<F>(&self, set_order: Ordering, fetch_order: Ordering, mut f: F)
+        -> Result<Option<u64>, Option<u64>>
+    where
+        F: FnMut(Option<u64>) -> Option<Option<u64>>,
+    {
+        self.value
+            .fetch_update(set_order, fetch_order, |raw| f(from_raw(raw)).map(to_raw))
+            .map(from_raw)
+            .map_err(from_raw)
+    }
+
+    /// Returns a mutable reference to the contained value.
+    ///
+    /// This is safe because the mutable reference guarantees that no other thread is concurrently accessing it.
+    pub fn get_mut(&mut self) -> &mut IntSentinel {
+        &mut IntSentinel::from_raw_slice_mut(::core::slice::from_mut(self.value.get_mut()))[0]
+    }
+
+    /// Consumes the atomic and returns the contained value.
+    pub fn into_inner(self) -> Option<u64> {
+        from_raw(self.value.into_inner())
+    }
+}
+
+impl Default for AtomicIntSentinel {
+    fn default() -> Self {
+        AtomicIntSentinel::new_none()
+    }
+}
+
+impl From<Option<u64>> for AtomicIntSentinel {
+    fn from(option: Option<u64>) -> Self {
+        AtomicIntSentinel::new(option)
+    }
+}
+
+impl From<IntSentinel> for AtomicIntSentinel {
+    fn from(sentinel: IntSentinel) -> Self {
+        AtomicIntSentinel { value: AtomicU64::new(unsafe { sentinel.to_u64_unchecked() }) }
+    }
+}
+
+impl fmt::Debug for AtomicIntSentinel {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(&self.load(Ordering::Relaxed), f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::vec::Vec;
+    use std::sync::atomic::Ordering::SeqCst;
+    use std::sync::Arc;
+    use std::thread;
+
+    use super::*;
+
+    #[test]
+    fn load_store() {
+        let slot = AtomicIntSentinel::default();
+        assert_eq!(slot.load(SeqCst), None);
+        slot.store(Some(42), SeqCst);
+        assert_eq!(slot.load(SeqCst), Some(42));
+        assert_eq!(slot.swap(None, SeqCst), Some(42));
+        assert_eq!(slot.swap(Some(7), SeqCst), None);
+        assert_eq!(slot.take(SeqCst), Some(7));
+        assert_eq!(slot.into_inner(), None);
+    }
+
+    #[test]
+    fn compare_exchange() {
+        let slot = AtomicIntSentinel::new(Some(1));
+        assert_eq!(slot.compare_exchange(None, Some(2), SeqCst, SeqCst), Err(Some(1)));
+        assert_eq!(slot.compare_exchange(Some(1), None, SeqCst, SeqCst), Ok(Some(1)));
+        assert_eq!(slot.set_if_none(3, SeqCst, SeqCst), Ok(()));
+        assert_eq!(slot.set_if_none(4, SeqCst, SeqCst), Err(3));
+    }
+
+    #[test]
+    fn fetch_update() {
+        let slot = AtomicIntSentinel::new(None);
+        assert_eq!(slot.fetch_update(SeqCst, SeqCst, |_| None), Err(None));
+        assert_eq!(slot.fetch_update(SeqCst, SeqCst, |x| Some(Some(x.unwrap_or(0) + 1))), Ok(None));
+        assert_eq!(slot.load(SeqCst), Some(1));
+    }
+
+    #[test]
+    fn get_mut() {
+        let mut slot = AtomicIntSentinel::from(IntSentinel::new_with_some(1));
+        slot.get_mut().insert(2);
+        assert_eq!(slot.load(SeqCst), Some(2));
+    }
+
+    #[test]
+    fn single_claimant() {
+        let slot = Arc::new(AtomicIntSentinel::new_none());
+        let handles: Vec<_> = (0..8)
+            .map(|worker| {
+                let slot = slot.clone();
+                thread::spawn(move || slot.set_if_none(worker, SeqCst, SeqCst).is_ok())
+            })
+            .collect();
+        let claimed = handles.into_iter().map(|handle| handle.join().unwrap()).filter(|&claimed| claimed).count();
+        assert_eq!(claimed, 1);
+        assert!(slot.load(SeqCst).is_some());
+    }
+
+    #[should_panic]
+    #[test]
+    fn store_illegal_value() {
+        AtomicIntSentinel::new_none().store(Some(u64::MAX), SeqCst);
+    }
+
+    #[test]
+    fn fetch_update_illegal_value() {
+        let slot = Arc::new(AtomicIntSentinel::new(Some(1)));
+        let panicking = slot.clone();
+        let result = thread::spawn(move || panicking.fetch_update(SeqCst, SeqCst, |_| Some(Some(u64::MAX)))).join();
+        assert!(result.is_err());
+        assert_eq!(slot.load(SeqCst), Some(1));
+    }
+}
@@ -0,0 +1,187 @@
+//! Bulk conversions between slices of `Option<u64>` and slices of `IntSentinel`.
+//!
+//! These functions are equivalent to converting each element with `From`, but are written without per-element
+//! branches so that the compiler can auto-vectorize them.
+//!
+//! # Examples
+//!
+//! ```rust
+//! # use sentinel_int::int_sentinel::IntSentinel;
+//! use sentinel_int::bulk;
+//! let options = [Some(1), None, Some(3)];
+//! let mut sentinels: [IntSentinel; 3] = Default::default();
+//! bulk::encode_slice(&options, &mut sentinels);
+//!
+//! let mut decoded = [None; 3];
+//! bulk::decode_slice(&sentinels, &mut decoded);
+//! assert_eq!(decoded, options);
+//! ```
+
+use core::fmt;
+#[cfg(feature = "std")]
+use std::error::Error;
+
+use error::SentinelError;
+use int_sentinel::IntSentinelWith;
+
+/// The number of elements that are validated at once by `try_encode_slice`.
+const CHUNK_LEN: usize = 64;
+
+/// The error returned by `try_encode_slice` when the source slice contains an illegal `Some(sentinel)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct EncodeError {
+    index: usize,
+    error: SentinelError<u64>,
+}
+
+impl EncodeError {
+    /// Returns the index of the first illegal element of the source slice.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Returns the illegal value, i.e. the sentinel.
+    pub fn value(&self) -> u64 {
+        self.error.value()
+    }
+}
+
+impl fmt::Display for EncodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "At index {}: {}", self.index, self.error)
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for EncodeError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+/// Encodes `src` into `dst`.
+///
+/// # Panics
+///
+/// This function panics if the slices have different lengths, or if `src` contains `Some(sentinel)`.
+pub fn encode_slice<const SENTINEL: u64>(src: &[Option<u64>], dst: &mut [IntSentinelWith<SENTINEL>]) {
+    if let Err(error) = try_encode_slice(src, dst) {
+        panic!("{}", error);
+    }
+}
+
+/// Encodes `src` into `dst`, or returns the index of the first `Some(sentinel)` of `src`.
+///
+/// When an error is returned, the elements of `dst` preceding the illegal element may or may not have been
+/// written, and the following ones are left untouched.
+///
+/// # Panics
+///
+/// This function panics if the slices have different lengths.
+///
+/// # Examples
+///
+/// ```rust
+/// # use sentinel_int::int_sentinel::IntSentinel;
+/// # use sentinel_int::bulk;
+/// let options = [Some(1), None, Some(u64::MAX)];
+/// let mut sentinels: [IntSentinel; 3] = Default::default();
+/// let error = bulk::try_encode_slice(&options, &mut sentinels).unwrap_err();
+/// assert_eq!(error.index(), 2);
+/// ```
+pub fn try_encode_slice<const SENTINEL: u64>(
+    src: &[Option<u64>],
+    dst: &mut [IntSentinelWith<SENTINEL>],
+) -> Result<(), EncodeError> {
+    assert_eq!(src.len(), dst.len(), "source and destination slices have different lengths");
+    let dst = IntSentinelWith::as_raw_slice_mut(dst);
+    for (chunk_index, (src, dst)) in src.chunks(CHUNK_LEN).zip(dst.chunks_mut(CHUNK_LEN)).enumerate() {
+        let illegal = src.iter().fold(false, |illegal, &option| illegal | (option == Some(SENTINEL)));
+        if illegal {
+            let index = src.iter().position(|&option| option == Some(SENTINEL)).unwrap();
+            return Err(EncodeError {
+                index: chunk_index * CHUNK_LEN + index,
+                error: SentinelError::new(SENTINEL),
+            });
+        }
+        for (dst, &option) in dst.iter_mut().zip(src) {
+            *dst = option.unwrap_or(SENTINEL);
+        }
+    }
+    Ok(())
+}
+
+/// Decodes `src` into `dst`.
+///
+/// # Panics
+///
+/// This function panics if the slices have different lengths.
+pub fn decode_slice<const SENTINEL: u64>(src: &[IntSentinelWith<SENTINEL>], dst: &mut [Option<u64>]) {
+    assert_eq!(src.len(), dst.len(), "source and destination slices have different lengths");
+    for (dst, &raw) in dst.iter_mut().zip(IntSentinelWith::as_raw_slice(src)) {
+        *dst = if raw == SENTINEL { None } else { Some(raw) };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::vec::Vec;
+
+    use int_sentinel::IntSentinel;
+
+    use super::*;
+
+    fn options(len: usize) -> Vec<Option<u64>> {
+        (0..len as u64).map(|i| if i % 3 == 0 { None } else { Some(i * 7) }).collect()
+    }
+
+    #[test]
+    fn round_trip() {
+        let options = options(1000);
+        let mut sentinels: Vec<IntSentinel> = vec![IntSentinel::NONE; options.len()];
+        encode_slice(&options, &mut sentinels);
+        for (sentinel, option) in sentinels.iter().zip(&options) {
+            assert_eq!(sentinel.to_option(), *option);
+        }
+        let mut decoded = vec![Some(0); options.len()];
+        decode_slice(&sentinels, &mut decoded);
+        assert_eq!(decoded, options);
+    }
+
+    #[test]
+    fn custom_sentinel() {
+        let options = [Some(u64::MAX), None, Some(1)];
+        let mut sentinels = [IntSentinelWith::<0>::NONE; 3];
+        encode_slice(&options, &mut sentinels);
+        assert_eq!(IntSentinelWith::as_raw_slice(&sentinels), &[u64::MAX, 0, 1]);
+        let mut decoded = [None; 3];
+        decode_slice(&sentinels, &mut decoded);
+        assert_eq!(decoded, options);
+    }
+
+    #[test]
+    fn first_illegal_index() {
+        let mut options = options(1000);
+        options[700] = Some(u64::MAX);
+        options[900] = Some(u64::MAX);
+        let mut sentinels: Vec<IntSentinel> = vec![IntSentinel::NONE; options.len()];
+        let error = try_encode_slice(&options, &mut sentinels).unwrap_err();
+        assert_eq!(error.index(), 700);
+        assert_eq!(error.value(), u64::MAX);
+        assert!(sentinels[701..].iter().all(IntSentinel::is_none));
+    }
+
+    #[should_panic]
+    #[test]
+    fn illegal_value() {
+        let mut sentinels: [IntSentinel; 1] = Default::default();
+        encode_slice(&[Some(u64::MAX)], &mut sentinels);
+    }
+
+    #[should_panic]
+    #[test]
+    fn length_mismatch() {
+        let mut decoded = [None; 2];
+        decode_slice::<{ u64::MAX }>(&[IntSentinel::NONE], &mut decoded);
+    }
+}
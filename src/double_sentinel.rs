@@ -0,0 +1,275 @@
+//! A compact representation for `Option<Option<u64>>`.
+//!
+//! `DoubleSentinel` reserves two raw values: `u64::MAX` represents the outer `None`, e.g. "not loaded",
+//! and `u64::MAX - 1` represents `Some(None)`, e.g. "loaded but absent". Any other raw value is a `Some(Some(_))`.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use sentinel_int::double_sentinel::DoubleSentinel;
+//! let not_loaded = DoubleSentinel::NONE;
+//! let absent = DoubleSentinel::SOME_NONE;
+//! let present = DoubleSentinel::new_with_some(42);
+//! assert_eq!(not_loaded.to_option(), None);
+//! assert_eq!(absent.to_option(), Some(None));
+//! assert_eq!(present.to_option(), Some(Some(42)));
+//! assert_eq!(absent.flatten(), None);
+//! assert_eq!(present.flatten(), Some(42));
+//! ```
+
+use core::cmp::Ordering;
+use core::convert::TryFrom;
+
+use error::SentinelError;
+
+/// A compact representation for `Option<Option<u64>>`, reserving `u64::MAX` for `None`
+/// and `u64::MAX - 1` for `Some(None)`.
+///
+/// Instances are ordered like the corresponding `Option<Option<u64>>`: `None`, then `Some(None)`, then the values.
+/// The `Default` instance contains `None`.
+///
+/// # Layout
+///
+/// This type is guaranteed to have the same layout as `u64`, and every `u64` bit pattern is a valid instance.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct DoubleSentinel {
+    value: u64,
+}
+
+impl DoubleSentinel {
+    /// An instance containing `None`, see `new_none()`.
+    pub const NONE: Self = DoubleSentinel { value: u64::MAX };
+
+    /// An instance containing `Some(None)`, see `new_some_none()`.
+    pub const SOME_NONE: Self = DoubleSentinel { value: u64::MAX - 1 };
+
+    /// The maximum value that can be represented by this type, see `max_value()`.
+    pub const MAX: u64 = u64::MAX - 2;
+
+    /// The minimum value that can be represented by this type, see `min_value()`.
+    pub const MIN: u64 = u64::MIN;
+
+    /// The maximum value that can be represented by this type.
+    pub const fn max_value() -> u64 {
+        Self::MAX
+    }
+
+    /// The minimum value that can be represented by this type.
+    pub const fn min_value() -> u64 {
+        Self::MIN
+    }
+
+    /// Returns `true` if `value` can be stored as a `Some(Some(_))`, i.e. if it is not one of the two sentinels.
+    pub const fn is_valid(value: u64) -> bool {
+        value <= Self::MAX
+    }
+
+    /// Constructs a new `DoubleSentinel` containing `None`.
+    pub const fn new_none() -> Self {
+        Self::NONE
+    }
+
+    /// Constructs a new `DoubleSentinel` containing `Some(None)`.
+    pub const fn new_some_none() -> Self {
+        Self::SOME_NONE
+    }
+
+    /// Constructs a new `DoubleSentinel` containing `Some(Some(value))`.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `value` is not valid (i.e., if it is greater than `max_value()`).
+    /// When evaluated in a `const` context, the panic is reported as a compilation error.
+    ///
+    /// ```compile_fail
+    /// # use sentinel_int::double_sentinel::DoubleSentinel;
+    /// const SENTINEL: DoubleSentinel = DoubleSentinel::new_with_some(u64::MAX - 1);
+    /// ```
+    pub const fn new_with_some(value: u64) -> Self {
+        match DoubleSentinel::try_new_with_some(value) {
+            Ok(sentinel) => sentinel,
+            Err(_) => panic!("Illegal value: the sentinel values cannot be stored as a `Some(Some(_))`."),
+        }
+    }
+
+    /// Constructs a new `DoubleSentinel` containing `Some(Some(value))`,
+    /// or returns an error if `value` is not valid (i.e., if it is greater than `max_value()`).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use sentinel_int::double_sentinel::DoubleSentinel;
+    /// assert!(DoubleSentinel::try_new_with_some(42).is_ok());
+    /// assert_eq!(DoubleSentinel::try_new_with_some(u64::MAX - 1).unwrap_err().value(), u64::MAX - 1);
+    /// ```
+    pub const fn try_new_with_some(value: u64) -> Result<Self, SentinelError<u64>> {
+        if !Self::is_valid(value) {
+            return Err(SentinelError::new(value));
+        }
+        Ok(DoubleSentinel { value })
+    }
+
+    /// Constructs a new `DoubleSentinel` from an `Option<Option<u64>>`,
+    /// or returns an error if it contains one of the sentinel values.
+    ///
+    /// This is the fallible counterpart of `From<Option<Option<u64>>>`.
+    pub const fn try_from_option(option: Option<Option<u64>>) -> Result<Self, SentinelError<u64>> {
+        match option {
+            Some(Some(value)) => DoubleSentinel::try_new_with_some(value),
+            Some(None) => Ok(DoubleSentinel::new_some_none()),
+            None => Ok(DoubleSentinel::new_none()),
+        }
+    }
+
+    /// Returns an `Option<Option<u64>>` corresponding to the value contained in this instance.
+    pub const fn to_option(&self) -> Option<Option<u64>> {
+        match self.value {
+            u64::MAX => None,
+            value if value == u64::MAX - 1 => Some(None),
+            value => Some(Some(value)),
+        }
+    }
+
+    /// Returns the contained value, merging `None` and `Some(None)` into `None`.
+    pub const fn flatten(&self) -> Option<u64> {
+        if Self::is_valid(self.value) {
+            Some(self.value)
+        } else {
+            None
+        }
+    }
+
+    /// Returns `true` if this instance contains `Some(_)`, i.e. `Some(None)` or a value.
+    pub const fn is_some(&self) -> bool {
+        self.value != u64::MAX
+    }
+
+    /// Returns `true` if this instance contains `None`.
+    pub const fn is_none(&self) -> bool {
+        self.value == u64::MAX
+    }
+
+    /// Returns `true` if this instance contains `Some(None)`.
+    pub const fn is_some_none(&self) -> bool {
+        self.value == u64::MAX - 1
+    }
+
+    /// Constructs a new `DoubleSentinel` from a raw value without checking the sentinel values.
+    ///
+    /// # Safety
+    ///
+    /// If using this function to create a `DoubleSentinel`, `u64::MAX` will be transformed into a `None` value,
+    /// `u64::MAX - 1` into a `Some(None)` value, and any other value will be mapped to a `Some(Some(_))`
+    /// of this value.
+    pub const unsafe fn unchecked_new(value: u64) -> Self {
+        DoubleSentinel { value }
+    }
+
+    /// Returns the raw contained value without a check.
+    ///
+    /// # Safety
+    ///
+    /// This method returns `u64::MAX` when the instance contains `None`, `u64::MAX - 1` when it contains
+    /// `Some(None)`, and the contained value otherwise.
+    pub const unsafe fn to_u64_unchecked(&self) -> u64 {
+        self.value
+    }
+}
+
+impl Default for DoubleSentinel {
+    fn default() -> Self {
+        DoubleSentinel::new_none()
+    }
+}
+
+impl PartialOrd for DoubleSentinel {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for DoubleSentinel {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.to_option().cmp(&other.to_option())
+    }
+}
+
+impl From<Option<Option<u64>>> for DoubleSentinel {
+    /// # Panics
+    ///
+    /// This function panics if `option` is `Some(Some(value))` with `value` greater than `max_value()`.
+    fn from(option: Option<Option<u64>>) -> Self {
+        match DoubleSentinel::try_from_option(option) {
+            Ok(sentinel) => sentinel,
+            Err(error) => panic!("{}", error),
+        }
+    }
+}
+
+impl TryFrom<u64> for DoubleSentinel {
+    type Error = SentinelError<u64>;
+
+    fn try_from(value: u64) -> Result<Self, Self::Error> {
+        DoubleSentinel::try_new_with_some(value)
+    }
+}
+
+impl From<DoubleSentinel> for Option<Option<u64>> {
+    fn from(sentinel: DoubleSentinel) -> Self {
+        sentinel.to_option()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::mem::size_of;
+
+    use super::*;
+
+    const OPTIONS: [Option<Option<u64>>; 5] = [None, Some(None), Some(Some(0)), Some(Some(42)), Some(Some(u64::MAX - 2))];
+
+    #[test]
+    fn round_trip() {
+        for &option in OPTIONS.iter() {
+            let sentinel = DoubleSentinel::from(option);
+            assert_eq!(sentinel.to_option(), option);
+            assert_eq!(Option::<Option<u64>>::from(sentinel), option);
+            assert_eq!(sentinel.flatten(), option.and_then(|option| option));
+        }
+        assert_eq!(size_of::<DoubleSentinel>(), size_of::<u64>());
+    }
+
+    #[test]
+    fn raw_values() {
+        assert_eq!(unsafe { DoubleSentinel::NONE.to_u64_unchecked() }, u64::MAX);
+        assert_eq!(unsafe { DoubleSentinel::SOME_NONE.to_u64_unchecked() }, u64::MAX - 1);
+        assert!(unsafe { DoubleSentinel::unchecked_new(u64::MAX - 1) }.is_some_none());
+        assert!(DoubleSentinel::default().is_none());
+        assert!(DoubleSentinel::SOME_NONE.is_some());
+    }
+
+    #[test]
+    fn checked_construction() {
+        assert!(DoubleSentinel::try_from(u64::MAX).is_err());
+        assert!(DoubleSentinel::try_from(u64::MAX - 1).is_err());
+        assert_eq!(DoubleSentinel::try_from(u64::MAX - 2).unwrap().flatten(), Some(u64::MAX - 2));
+        assert!(DoubleSentinel::try_from_option(Some(Some(u64::MAX))).is_err());
+        assert_eq!(DoubleSentinel::try_from_option(Some(None)), Ok(DoubleSentinel::SOME_NONE));
+    }
+
+    #[test]
+    fn ordering_matches_option() {
+        for &a in OPTIONS.iter() {
+            for &b in OPTIONS.iter() {
+                assert_eq!(DoubleSentinel::from(a).cmp(&DoubleSentinel::from(b)), a.cmp(&b));
+            }
+        }
+    }
+
+    #[should_panic]
+    #[test]
+    fn illegal_value() {
+        let _ = DoubleSentinel::from(Some(Some(u64::MAX - 1)));
+    }
+}
@@ -0,0 +1,53 @@
+use core::fmt;
+#[cfg(feature = "std")]
+use std::error::Error;
+
+/// The error returned when trying to store the sentinel value as a `Some`.
+///
+/// The offending value is carried by the error and can be retrieved with `value()`.
+///
+/// This error is returned by `try_new_with_some`, `try_from_option` and the `TryFrom` implementation from the integer
+/// type, but not by `TryFrom<Option<_>>`: that is the standard library's blanket implementation derived from
+/// `From<Option<_>>`, which panics on the sentinel value. Use `try_from_option` to convert an `Option` without
+/// panicking.
+///
+/// # Examples
+///
+/// ```rust
+/// # use sentinel_int::int_sentinel::IntSentinel;
+/// let error = IntSentinel::try_new_with_some(u64::MAX).unwrap_err();
+/// assert_eq!(error.value(), u64::MAX);
+/// assert_eq!(error.to_string(), format!("Illegal value: {} is the sentinel value.", u64::MAX));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SentinelError<T> {
+    value: T,
+}
+
+impl<T> SentinelError<T> {
+    pub(crate) const fn new(value: T) -> Self {
+        SentinelError { value }
+    }
+
+    /// Returns the value that could not be stored.
+    pub const fn value(&self) -> T
+    where
+        T: Copy,
+    {
+        self.value
+    }
+
+    /// Consumes the error, returning the value that could not be stored.
+    pub fn into_value(self) -> T {
+        self.value
+    }
+}
+
+impl<T: fmt::Debug> fmt::Display for SentinelError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Illegal value: {:?} is the sentinel value.", self.value)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: fmt::Debug> Error for SentinelError<T> {}
@@ -0,0 +1,479 @@
+//! Optional generational handles, packed into a single `u64`.
+//!
+//! A `GenerationalSentinelWith<INDEX_BITS>` stores an index in its `INDEX_BITS` low bits and a generation in the
+//! remaining high bits, and `GenerationalSentinel` is the common 32-bit split. The all-ones pattern is the sentinel and
+//! represents `None`.
+//!
+//! The all-ones generation is never used: `next_generation` wraps from `MAX_GENERATION` back to `0`, so that every
+//! index can be used with every generation without ever producing the sentinel pattern.
+//!
+//! The `GenerationalArena<T>` hands out these handles, and bumps the generation of a slot when its value is removed,
+//! so that stale handles are detected on access instead of silently pointing to a new value.
+//!
+//! # Examples
+//!
+#![cfg_attr(feature = "std", doc = "```rust")]
+#![cfg_attr(not(feature = "std"), doc = "```ignore")]
+//! use sentinel_int::generational::{GenerationalArena, GenerationalSentinel};
+//! let mut entities = GenerationalArena::new();
+//! let player = entities.insert("player");
+//! assert_eq!(entities.get(player), Some(&"player"));
+//!
+//! entities.remove(player);
+//! let monster = entities.insert("monster");
+//! // The slot is reused, but the stale handle does not give access to the new value.
+//! assert_eq!(monster.index(), player.index());
+//! assert_eq!(entities.get(player), None);
+//! assert_eq!(entities.get(GenerationalSentinel::NONE), None);
+//! ```
+
+use core::fmt;
+#[cfg(feature = "std")]
+use core::ops::{Index, IndexMut};
+#[cfg(feature = "std")]
+use std::error::Error;
+#[cfg(feature = "std")]
+use std::vec::Vec;
+
+use int_sentinel::IntSentinel;
+#[cfg(feature = "std")]
+use slab::SentinelSlab;
+
+/// The error returned when the index or the generation of a handle does not fit in its bits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GenerationalError {
+    /// The index is greater than `MAX_INDEX`.
+    Index(u64),
+    /// The generation is greater than `MAX_GENERATION`.
+    Generation(u64),
+}
+
+impl fmt::Display for GenerationalError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            GenerationalError::Index(index) => write!(f, "Illegal index: {} is out of range.", index),
+            GenerationalError::Generation(generation) => {
+                write!(f, "Illegal generation: {} is out of range.", generation)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for GenerationalError {}
+
+/// An optional handle made of a 32-bit index and a 32-bit generation, packed into a `u64` whose all-ones pattern
+/// represents `None`.
+///
+/// This is `GenerationalSentinelWith` with its default split: use `GenerationalSentinelWith` directly for other splits.
+///
+/// # Examples
+///
+/// ```rust
+/// # use sentinel_int::generational::GenerationalSentinel;
+/// let handle = GenerationalSentinel::new(7, 3);
+/// assert_eq!(handle.to_option(), Some((7, 3)));
+/// assert_eq!(GenerationalSentinel::MAX_INDEX, u32::MAX as u64);
+/// ```
+pub type GenerationalSentinel = GenerationalSentinelWith<32>;
+
+/// An optional handle made of an index and a generation, packed into a `u64` whose all-ones pattern represents `None`.
+///
+/// Most code should use the `GenerationalSentinel` alias, which uses 32 bits for the index.
+/// `INDEX_BITS` is the number of bits of the index, between 1 and 62. The remaining bits hold the generation: at least
+/// two bits are needed, since the all-ones generation is reserved and a single generation could not tell a stale handle
+/// from a current one. Other bit splits are rejected at compile time:
+///
+/// ```rust,compile_fail
+/// # use sentinel_int::generational::GenerationalSentinelWith;
+/// let handle = GenerationalSentinelWith::<63>::new(0, 0);
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[repr(transparent)]
+pub struct GenerationalSentinelWith<const INDEX_BITS: u32> {
+    value: IntSentinel,
+}
+
+impl<const INDEX_BITS: u32> GenerationalSentinelWith<INDEX_BITS> {
+    /// The number of bits of the generation.
+    pub const GENERATION_BITS: u32 = {
+        assert!(INDEX_BITS > 0 && INDEX_BITS < 63, "INDEX_BITS must be between 1 and 62");
+        64 - INDEX_BITS
+    };
+
+    /// The greatest index.
+    pub const MAX_INDEX: u64 = u64::MAX >> Self::GENERATION_BITS;
+
+    /// The greatest generation. The all-ones generation is reserved, so that no handle is the sentinel pattern.
+    pub const MAX_GENERATION: u64 = (u64::MAX >> INDEX_BITS) - 1;
+
+    /// The handle containing `None`.
+    pub const NONE: Self = GenerationalSentinelWith { value: IntSentinel::NONE };
+
+    /// Constructs a new `GenerationalSentinel` containing `None`.
+    pub const fn new_none() -> Self {
+        Self::NONE
+    }
+
+    /// Constructs a new `GenerationalSentinel` containing the provided index and generation.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `index` is greater than `MAX_INDEX` or `generation` is greater than `MAX_GENERATION`.
+    pub const fn new(index: u64, generation: u64) -> Self {
+        match Self::try_new(index, generation) {
+            Ok(handle) => handle,
+            Err(GenerationalError::Index(_)) => panic!("Illegal index: the index is out of range."),
+            Err(GenerationalError::Generation(_)) => panic!("Illegal generation: the generation is out of range."),
+        }
+    }
+
+    /// Constructs a new `GenerationalSentinel` containing the provided index and generation,
+    /// or returns an error if one of them is out of range.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use sentinel_int::generational::{GenerationalError, GenerationalSentinelWith};
+    /// type Handle = GenerationalSentinelWith<48>;
+    /// assert!(Handle::try_new(1 << 47, 0xFFFE).is_ok());
+    /// assert_eq!(Handle::try_new(1 << 48, 0), Err(GenerationalError::Index(1 << 48)));
+    /// assert_eq!(Handle::try_new(0, 0xFFFF), Err(GenerationalError::Generation(0xFFFF)));
+    /// ```
+    pub const fn try_new(index: u64, generation: u64) -> Result<Self, GenerationalError> {
+        if index > Self::MAX_INDEX {
+            return Err(GenerationalError::Index(index));
+        }
+        if generation > Self::MAX_GENERATION {
+            return Err(GenerationalError::Generation(generation));
+        }
+        // The generation is not all-ones, hence the packed value is not the sentinel.
+        let value = unsafe { IntSentinel::unchecked_new(generation << INDEX_BITS | index) };
+        Ok(GenerationalSentinelWith { value })
+    }
+
+    /// Returns the generation that follows `generation`, wrapping from `MAX_GENERATION` back to `0`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use sentinel_int::generational::GenerationalSentinelWith;
+    /// type Handle = GenerationalSentinelWith<62>;
+    /// assert_eq!(Handle::MAX_GENERATION, 2);
+    /// assert_eq!(Handle::next_generation(1), 2);
+    /// assert_eq!(Handle::next_generation(2), 0);
+    /// ```
+    pub const fn next_generation(generation: u64) -> u64 {
+        if generation >= Self::MAX_GENERATION {
+            0
+        } else {
+            generation + 1
+        }
+    }
+
+    /// Returns the index and the generation contained in this instance, or `None`.
+    pub const fn to_option(self) -> Option<(u64, u64)> {
+        match self.value.to_option() {
+            Some(value) => Some((value & Self::MAX_INDEX, value >> INDEX_BITS)),
+            None => None,
+        }
+    }
+
+    /// Returns the index contained in this instance, or `None`.
+    pub const fn index(self) -> Option<u64> {
+        match self.to_option() {
+            Some((index, _)) => Some(index),
+            None => None,
+        }
+    }
+
+    /// Returns the generation contained in this instance, or `None`.
+    pub const fn generation(self) -> Option<u64> {
+        match self.to_option() {
+            Some((_, generation)) => Some(generation),
+            None => None,
+        }
+    }
+
+    /// Returns `true` if this instance contains a handle.
+    pub const fn is_some(self) -> bool {
+        self.value.is_some()
+    }
+
+    /// Returns `true` if this instance contains `None`.
+    pub const fn is_none(self) -> bool {
+        self.value.is_none()
+    }
+
+    /// Returns the packed representation as an `IntSentinel`.
+    pub const fn to_sentinel(self) -> IntSentinel {
+        self.value
+    }
+}
+
+impl<const INDEX_BITS: u32> fmt::Debug for GenerationalSentinelWith<INDEX_BITS> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.to_option() {
+            Some((index, generation)) => {
+                f.debug_struct("GenerationalSentinel").field("index", &index).field("generation", &generation).finish()
+            }
+            None => f.write_str("GenerationalSentinel(None)"),
+        }
+    }
+}
+
+impl<const INDEX_BITS: u32> From<Option<(u64, u64)>> for GenerationalSentinelWith<INDEX_BITS> {
+    /// # Panics
+    ///
+    /// This function panics if the index or the generation is out of range.
+    fn from(option: Option<(u64, u64)>) -> Self {
+        match option {
+            Some((index, generation)) => GenerationalSentinelWith::new(index, generation),
+            None => GenerationalSentinelWith::new_none(),
+        }
+    }
+}
+
+impl<const INDEX_BITS: u32> From<GenerationalSentinelWith<INDEX_BITS>> for Option<(u64, u64)> {
+    fn from(handle: GenerationalSentinelWith<INDEX_BITS>) -> Self {
+        handle.to_option()
+    }
+}
+
+/// A collection of values addressed by `GenerationalSentinel` handles.
+///
+/// This is `GenerationalArenaWith` with its default split: use `GenerationalArenaWith` directly to address the values
+/// with handles of another split.
+///
+/// # Examples
+///
+/// ```rust
+/// # use sentinel_int::generational::GenerationalArena;
+/// let mut arena = GenerationalArena::new();
+/// let handle = arena.insert(42);
+/// assert_eq!(arena[handle], 42);
+/// ```
+#[cfg(feature = "std")]
+pub type GenerationalArena<T> = GenerationalArenaWith<T, 32>;
+
+/// A collection of values addressed by `GenerationalSentinelWith<INDEX_BITS>` handles.
+///
+/// Most code should use the `GenerationalArena` alias, which uses `GenerationalSentinel` handles.
+/// The values are stored in a `SentinelSlab`, whose keys are the indices of the handles, along with the current
+/// generation of each slot. Removing a value bumps the generation of its slot, so that the handles to the removed value
+/// are rejected by every accessor, even after the slot is reused.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone)]
+pub struct GenerationalArenaWith<T, const INDEX_BITS: u32> {
+    slab: SentinelSlab<T>,
+    /// The current generation of each slot of the slab.
+    generations: Vec<u64>,
+}
+
+#[cfg(feature = "std")]
+impl<T, const INDEX_BITS: u32> GenerationalArenaWith<T, INDEX_BITS> {
+    /// Constructs a new, empty `GenerationalArena`.
+    pub fn new() -> Self {
+        GenerationalArenaWith { slab: SentinelSlab::new(), generations: Vec::new() }
+    }
+
+    /// Returns the number of values.
+    pub fn len(&self) -> usize {
+        self.slab.len()
+    }
+
+    /// Returns `true` if there are no values.
+    pub fn is_empty(&self) -> bool {
+        self.slab.is_empty()
+    }
+
+    /// Inserts `value`, returning its handle.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if every index up to `MAX_INDEX` is in use.
+    pub fn insert(&mut self, value: T) -> GenerationalSentinelWith<INDEX_BITS> {
+        match self.try_insert(value) {
+            Ok(handle) => handle,
+            Err(_) => panic!("GenerationalArena is full"),
+        }
+    }
+
+    /// Inserts `value`, returning its handle, or gives `value` back if every index up to `MAX_INDEX` is in use.
+    pub fn try_insert(&mut self, value: T) -> Result<GenerationalSentinelWith<INDEX_BITS>, T> {
+        // Every vacant slot of the slab is in its free list, so that a new slot is only used when none is vacant.
+        let full = self.slab.len() == self.slab.slots();
+        if full && self.slab.slots() as u64 > GenerationalSentinelWith::<INDEX_BITS>::MAX_INDEX {
+            return Err(value);
+        }
+        let index = self.slab.insert(value);
+        if index == self.generations.len() {
+            self.generations.push(0);
+        }
+        Ok(GenerationalSentinelWith::new(index as u64, self.generations[index]))
+    }
+
+    /// Removes the value of `handle`, returning it, or returns `None` if the handle is `None` or stale.
+    pub fn remove(&mut self, handle: GenerationalSentinelWith<INDEX_BITS>) -> Option<T> {
+        let index = self.current_index(handle)?;
+        let generation = &mut self.generations[index];
+        *generation = GenerationalSentinelWith::<INDEX_BITS>::next_generation(*generation);
+        self.slab.remove(index)
+    }
+
+    /// Returns `true` if `handle` refers to a value of this arena.
+    pub fn contains(&self, handle: GenerationalSentinelWith<INDEX_BITS>) -> bool {
+        self.current_index(handle).is_some()
+    }
+
+    /// Returns the value of `handle`, or `None` if the handle is `None` or stale.
+    pub fn get(&self, handle: GenerationalSentinelWith<INDEX_BITS>) -> Option<&T> {
+        self.slab.get(self.current_index(handle)?)
+    }
+
+    /// Returns the value of `handle` mutably, or `None` if the handle is `None` or stale.
+    pub fn get_mut(&mut self, handle: GenerationalSentinelWith<INDEX_BITS>) -> Option<&mut T> {
+        let index = self.current_index(handle)?;
+        self.slab.get_mut(index)
+    }
+
+    /// Returns an iterator over the values, yielding their handle along with them, in index order.
+    pub fn iter(&self) -> impl Iterator<Item = (GenerationalSentinelWith<INDEX_BITS>, &T)> {
+        let generations = &self.generations;
+        self.slab.iter().map(move |(index, value)| {
+            (GenerationalSentinelWith::new(index as u64, generations[index]), value)
+        })
+    }
+
+    /// Returns the index of `handle` if it refers to a value of this arena.
+    fn current_index(&self, handle: GenerationalSentinelWith<INDEX_BITS>) -> Option<usize> {
+        let (index, generation) = handle.to_option()?;
+        let index = index as usize;
+        if self.generations.get(index) == Some(&generation) && self.slab.contains(index) {
+            Some(index)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T, const INDEX_BITS: u32> Default for GenerationalArenaWith<T, INDEX_BITS> {
+    fn default() -> Self {
+        GenerationalArenaWith::new()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T, const INDEX_BITS: u32> Index<GenerationalSentinelWith<INDEX_BITS>> for GenerationalArenaWith<T, INDEX_BITS> {
+    type Output = T;
+
+    /// # Panics
+    ///
+    /// This function panics if `handle` is `None` or stale.
+    fn index(&self, handle: GenerationalSentinelWith<INDEX_BITS>) -> &T {
+        self.get(handle).expect("no value for this handle")
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T, const INDEX_BITS: u32> IndexMut<GenerationalSentinelWith<INDEX_BITS>> for GenerationalArenaWith<T, INDEX_BITS> {
+    /// # Panics
+    ///
+    /// This function panics if `handle` is `None` or stale.
+    fn index_mut(&mut self, handle: GenerationalSentinelWith<INDEX_BITS>) -> &mut T {
+        self.get_mut(handle).expect("no value for this handle")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn packing() {
+        let handle = GenerationalSentinel::new(7, 3);
+        assert_eq!(handle.to_option(), Some((7, 3)));
+        assert_eq!(unsafe { handle.to_sentinel().to_u64_unchecked() }, 3 << 32 | 7);
+        assert_eq!(GenerationalSentinel::default(), GenerationalSentinel::NONE);
+        assert_eq!(GenerationalSentinel::NONE.index(), None);
+        assert_eq!(GenerationalSentinel::from(Some((1, 2))).generation(), Some(2));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn default_split() {
+        // The aliases are plain types: their paths need no annotation in expressions.
+        let handle = GenerationalSentinel::new(1, 2);
+        assert_eq!(handle.index(), Some(1));
+        let mut arena = GenerationalArena::new();
+        let handle = arena.insert("value");
+        assert_eq!(handle.to_option(), Some((0, 0)));
+        assert_eq!(arena.get(GenerationalSentinel::new(0, 1)), None);
+    }
+
+    #[test]
+    fn bit_splits() {
+        assert_eq!(GenerationalSentinelWith::<1>::MAX_INDEX, 1);
+        assert_eq!(GenerationalSentinelWith::<1>::MAX_GENERATION, (1 << 63) - 2);
+        assert_eq!(GenerationalSentinelWith::<62>::MAX_INDEX, (1 << 62) - 1);
+        assert_eq!(GenerationalSentinelWith::<62>::MAX_GENERATION, 2);
+        assert_eq!(GenerationalSentinelWith::<62>::next_generation(0), 1);
+    }
+
+    #[test]
+    fn never_sentinel() {
+        type Handle = GenerationalSentinelWith<60>;
+        let mut generation = 0;
+        for _ in 0..40 {
+            let handle = Handle::new(Handle::MAX_INDEX, generation);
+            assert!(handle.is_some());
+            assert_eq!(handle.to_option(), Some((Handle::MAX_INDEX, generation)));
+            generation = Handle::next_generation(generation);
+        }
+        assert!(Handle::try_new(Handle::MAX_INDEX, Handle::MAX_GENERATION + 1).is_err());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn stale_handles() {
+        let mut arena = GenerationalArena::new();
+        let first = arena.insert(1);
+        let second = arena.insert(2);
+        arena[second] += 40;
+        assert_eq!(arena.remove(first), Some(1));
+        assert_eq!(arena.remove(first), None);
+        assert!(!arena.contains(first));
+        let third = arena.insert(3);
+        assert_eq!(third.to_option(), Some((0, 1)));
+        assert_eq!(arena.get(first), None);
+        assert_eq!(arena.get_mut(first), None);
+        assert_eq!(arena.get(third), Some(&3));
+        assert_eq!(arena.len(), 2);
+        let handles: Vec<_> = arena.iter().map(|(handle, &value)| (handle.to_option(), value)).collect();
+        assert_eq!(handles, vec![(Some((0, 1)), 3), (Some((1, 0)), 42)]);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn wrapping_generations() {
+        let mut arena = GenerationalArenaWith::<(), 62>::new();
+        let mut handles = Vec::new();
+        for _ in 0..4 {
+            let handle = arena.insert(());
+            handles.push(handle);
+            arena.remove(handle);
+        }
+        let generations: Vec<_> = handles.iter().map(|handle| handle.generation().unwrap()).collect();
+        assert_eq!(generations, vec![0, 1, 2, 0]);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn full() {
+        let mut arena = GenerationalArenaWith::<u8, 1>::new();
+        arena.insert(0);
+        arena.insert(1);
+        assert_eq!(arena.try_insert(2), Err(2));
+    }
+}
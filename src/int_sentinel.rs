@@ -0,0 +1,1192 @@
+use core::cmp::Ordering;
+use core::convert::TryFrom;
+use core::fmt;
+use core::str::FromStr;
+
+use error::SentinelError;
+use text::{self, DisplayWith, ParseSentinelError};
+
+macro_rules! int_sentinel {
+    ($name:ident, $generic:ident, $t:ident, $to_unchecked:ident, $sentinel:ident) => {
+        #[doc = concat!("A compact representation for `Option<", stringify!($t), ">`, obtained by using `",
+                        stringify!($t), "::", stringify!($sentinel), "` as a sentinel.")]
+        ///
+        #[doc = concat!("This is `", stringify!($generic), "` with its default sentinel: use `", stringify!($generic),
+                        "` directly to choose another one.")]
+        ///
+        #[doc = concat!("Compared to a NonZero implementation of ", stringify!($t),
+                        ", this implementation is easier to use as index in e.g. collections.")]
+        /// This representation is solely meant as a means of storing the `Option` more space-efficiently
+        /// (e.g. before sending on network, saving on disk, keeping in large in-memory structures).
+        /// Users are expected to use the `From` trait to convert it back to an `Option` before an actual use of the value.
+        ///
+        /// # Examples
+        ///
+        /// ```rust
+        #[doc = concat!("# use sentinel_int::int_sentinel::", stringify!($name), ";")]
+        #[doc = concat!("// Convert an option into an ", stringify!($name))]
+        #[doc = concat!("let sentinel = ", stringify!($name), "::from(Some(42", stringify!($t), ")); // The sentinel is \"just a ",
+                        stringify!($t), "\"")]
+        /// // [...]
+        /// // Convert back the sentinel into an Option
+        #[doc = concat!("let from_sentinel = Option::<", stringify!($t), ">::from(sentinel);")]
+        #[doc = concat!("assert_eq!(from_sentinel, Some(42", stringify!($t), "));")]
+        /// ```
+        ///
+        /// ```rust
+        #[doc = concat!("# use sentinel_int::int_sentinel::", stringify!($name), ";")]
+        #[doc = concat!("// Convert an option into an ", stringify!($name))]
+        #[doc = concat!("let sentinel = ", stringify!($name), "::from(None); // The sentinel is \"just a ",
+                        stringify!($t), "\"")]
+        /// // [...]
+        /// // Convert back the sentinel into an Option
+        #[doc = concat!("let from_sentinel = Option::<", stringify!($t), ">::from(sentinel);")]
+        /// assert_eq!(from_sentinel, None);
+        /// ```
+        ///
+        /// # Panics
+        ///
+        #[doc = concat!("`From<Option<", stringify!($t), ">>` panics if the option contains the sentinel value. Because of this `From`")]
+        #[doc = concat!("implementation, the standard library's blanket `TryFrom<Option<", stringify!($t), ">>` implementation applies too:")]
+        /// its error type is `Infallible`, and it panics in the same way.
+        /// Use `try_from_option` to convert untrusted input without panicking.
+        pub type $name = $generic<{ $t::$sentinel }>;
+
+        #[doc = concat!("A compact representation for `Option<", stringify!($t), ">`, obtained by using the `SENTINEL` const parameter as a sentinel.")]
+        ///
+        #[doc = concat!("Most code should use the `", stringify!($name), "` alias, which uses `", stringify!($t), "::",
+                        stringify!($sentinel), "` as the sentinel.")]
+        /// A custom sentinel is useful e.g. to match an existing on-disk format.
+        ///
+        /// # Examples
+        ///
+        /// ```rust
+        #[doc = concat!("# use sentinel_int::int_sentinel::", stringify!($generic), ";")]
+        #[doc = concat!("let sentinel = ", stringify!($generic), "::<0>::from(Some(42));")]
+        #[doc = concat!("assert_eq!(unsafe { sentinel.", stringify!($to_unchecked), "() }, 42);")]
+        #[doc = concat!("let sentinel = ", stringify!($generic), "::<0>::from(None);")]
+        #[doc = concat!("assert_eq!(unsafe { sentinel.", stringify!($to_unchecked), "() }, 0);")]
+        /// ```
+        ///
+        /// # Ordering
+        ///
+        #[doc = concat!("Instances are ordered like the corresponding `Option<", stringify!($t), ">`, i.e. `None` sorts before any value,")]
+        /// regardless of where the sentinel lies in the raw representation.
+        /// The `Default` instance contains `None`.
+        ///
+        /// # Layout
+        ///
+        #[doc = concat!("This type is guaranteed to have the same layout as `", stringify!($t),
+                        "`, and every `", stringify!($t), "` bit pattern is a valid instance:")]
+        /// the sentinel is read as `None`, and any other value as a `Some`.
+        /// This allows reinterpreting raw buffers without copying, see `from_raw_slice` and `as_raw_slice`.
+        ///
+        #[doc = concat!("Since every bit pattern is in use, there is no niche left for the compiler: `Option<",
+                        stringify!($name), ">` is as large as `Option<", stringify!($t), ">`.")]
+        /// Storing an "unset" state in addition to `None` requires reserving a second raw value,
+        /// as `double_sentinel::DoubleSentinel` does for `u64`.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        #[repr(transparent)]
+        pub struct $generic<const SENTINEL: $t> {
+            value: $t,
+        }
+
+        impl<const SENTINEL: $t> $generic<SENTINEL> {
+            #[doc = concat!("An instance containing `None`, see `new_none()`.")]
+            ///
+            /// # Examples
+            ///
+            /// ```rust
+            #[doc = concat!("# use sentinel_int::int_sentinel::", stringify!($name), ";")]
+            #[doc = concat!("static TABLE: [", stringify!($name), "; 2] = [", stringify!($name), "::NONE, ",
+                            stringify!($name), "::new_with_some(42)];")]
+            /// assert_eq!(TABLE[0].to_option(), None);
+            /// assert_eq!(TABLE[1].to_option(), Some(42));
+            /// ```
+            pub const NONE: Self = $generic { value: SENTINEL };
+
+            /// The maximum value that can be represented by this type, see `max_value()`.
+            pub const MAX: $t = if SENTINEL == $t::MAX { $t::MAX - 1 } else { $t::MAX };
+
+            /// The minimum value that can be represented by this type, see `min_value()`.
+            pub const MIN: $t = if SENTINEL == $t::MIN { $t::MIN + 1 } else { $t::MIN };
+
+            /// The sentinel value, see `sentinel()`.
+            pub const SENTINEL: $t = SENTINEL;
+
+            /// The maximum value that can be represented by this type.
+            ///
+            /// Values between `min_value()` and `max_value()` can still be invalid if the sentinel is not at one end
+            /// of the range: use `is_valid()` to check a specific value.
+            pub const fn max_value() -> $t {
+                Self::MAX
+            }
+
+            /// The minimum value that can be represented by this type.
+            ///
+            /// Values between `min_value()` and `max_value()` can still be invalid if the sentinel is not at one end
+            /// of the range: use `is_valid()` to check a specific value.
+            pub const fn min_value() -> $t {
+                Self::MIN
+            }
+
+            /// The sentinel value.
+            pub const fn sentinel() -> $t {
+                SENTINEL
+            }
+
+            /// Returns `true` if `value` can be stored as a `Some`, i.e. if it is not the sentinel value.
+            ///
+            /// # Examples
+            ///
+            /// ```rust
+            #[doc = concat!("# use sentinel_int::int_sentinel::", stringify!($generic), ";")]
+            #[doc = concat!("assert!(", stringify!($generic), "::<0>::is_valid(42));")]
+            #[doc = concat!("assert!(!", stringify!($generic), "::<0>::is_valid(0));")]
+            /// ```
+            pub const fn is_valid(value: $t) -> bool {
+                value != SENTINEL
+            }
+
+            #[doc = concat!("Constructs a new `", stringify!($name), "` containing `None`.")]
+            ///
+            /// # Examples
+            ///
+            /// ```rust
+            #[doc = concat!("# use sentinel_int::int_sentinel::", stringify!($name), ";")]
+            #[doc = concat!("let sentinel = ", stringify!($name), "::new_none();")]
+            /// assert_eq!(sentinel.to_option(), None);
+            /// ```
+            pub const fn new_none() -> Self {
+                Self::NONE
+            }
+
+            #[doc = concat!("Constructs a new `", stringify!($name), "` containing the provided `", stringify!($t), "`.")]
+            ///
+            /// # Panics
+            ///
+            /// This function panics if `value` is not valid (i.e., if it equals `sentinel()`).
+            /// When evaluated in a `const` context, the panic is reported as a compilation error.
+            ///
+            /// # Examples
+            ///
+            /// ```rust
+            #[doc = concat!("# use sentinel_int::int_sentinel::", stringify!($name), ";")]
+            #[doc = concat!("let sentinel = ", stringify!($name), "::new_with_some(42", stringify!($t), ");")]
+            #[doc = concat!("assert_eq!(sentinel.to_option(), Some(42", stringify!($t), "));")]
+            /// ```
+            ///
+            /// ```compile_fail
+            #[doc = concat!("# use sentinel_int::int_sentinel::", stringify!($name), ";")]
+            #[doc = concat!("const SENTINEL: ", stringify!($name), " = ", stringify!($name), "::new_with_some(", stringify!($t), "::", stringify!($sentinel), ");")]
+            /// ```
+            #[track_caller]
+            pub const fn new_with_some(value: $t) -> Self {
+                match $generic::try_new_with_some(value) {
+                    Ok(sentinel) => sentinel,
+                    Err(_) => panic!(concat!("Illegal value: the sentinel value cannot be stored as a `Some` of `",
+                                             stringify!($generic), "`.")),
+                }
+            }
+
+            #[doc = concat!("Constructs a new `", stringify!($name), "` containing the provided `", stringify!($t), "`,")]
+            /// or returns an error if `value` is not valid (i.e., if it equals `sentinel()`).
+            ///
+            /// # Examples
+            ///
+            /// ```rust
+            #[doc = concat!("# use sentinel_int::int_sentinel::", stringify!($name), ";")]
+            #[doc = concat!("let sentinel = ", stringify!($name), "::try_new_with_some(42", stringify!($t), ").unwrap();")]
+            #[doc = concat!("assert_eq!(sentinel.to_option(), Some(42", stringify!($t), "));")]
+            #[doc = concat!("let error = ", stringify!($name), "::try_new_with_some(", stringify!($name), "::sentinel()).unwrap_err();")]
+            #[doc = concat!("assert_eq!(error.value(), ", stringify!($name), "::sentinel());")]
+            /// ```
+            pub const fn try_new_with_some(value: $t) -> Result<Self, SentinelError<$t>> {
+                if value == SENTINEL {
+                    return Err(SentinelError::new(value));
+                }
+                Ok($generic { value })
+            }
+
+            #[doc = concat!("Constructs a new `", stringify!($name), "` from an `Option`,")]
+            /// or returns an error if it contains the sentinel value.
+            ///
+            #[doc = concat!("This is the fallible counterpart of `From<Option<", stringify!($t), ">>`.")]
+            ///
+            /// # Examples
+            ///
+            /// ```rust
+            #[doc = concat!("# use sentinel_int::int_sentinel::", stringify!($name), ";")]
+            #[doc = concat!("assert!(", stringify!($name), "::try_from_option(None).is_ok());")]
+            #[doc = concat!("assert!(", stringify!($name), "::try_from_option(Some(", stringify!($name), "::sentinel())).is_err());")]
+            /// ```
+            pub const fn try_from_option(option: Option<$t>) -> Result<Self, SentinelError<$t>> {
+                match option {
+                    Some(value) => $generic::try_new_with_some(value),
+                    None => Ok($generic::new_none())
+                }
+            }
+
+            /// Returns an `Option` corresponding to the value contained in this instance.
+            pub const fn to_option(&self) -> Option<$t> {
+                if self.value == SENTINEL {
+                    None
+                } else {
+                    Some(self.value)
+                }
+            }
+
+            /// Returns `true` if this instance contains a value.
+            pub const fn is_some(&self) -> bool {
+                self.value != SENTINEL
+            }
+
+            /// Returns `true` if this instance contains `None`.
+            pub const fn is_none(&self) -> bool {
+                self.value == SENTINEL
+            }
+
+            /// Maps the contained value with `f`, leaving `None` untouched.
+            ///
+            /// # Panics
+            ///
+            /// This function panics if `f` returns the sentinel value.
+            ///
+            /// # Examples
+            ///
+            /// ```rust
+            #[doc = concat!("# use sentinel_int::int_sentinel::", stringify!($name), ";")]
+            #[doc = concat!("let sentinel = ", stringify!($name), "::new_with_some(21);")]
+            /// assert_eq!(sentinel.map(|x| x * 2).to_option(), Some(42));
+            /// ```
+            #[track_caller]
+            pub fn map<F: FnOnce($t) -> $t>(self, f: F) -> Self {
+                if self.value == SENTINEL {
+                    self
+                } else {
+                    $generic::checked_new(f(self.value))
+                }
+            }
+
+            /// Returns `None` if this instance contains `None`, otherwise calls `f` with the contained value and
+            /// returns the result.
+            pub fn and_then<F: FnOnce($t) -> Self>(self, f: F) -> Self {
+                if self.value == SENTINEL {
+                    self
+                } else {
+                    f(self.value)
+                }
+            }
+
+            /// Returns `None` if this instance contains `None` or if `predicate` returns `false` for the contained
+            /// value, otherwise returns this instance unchanged.
+            pub fn filter<P: FnOnce(&$t) -> bool>(self, predicate: P) -> Self {
+                if self.value != SENTINEL && predicate(&self.value) {
+                    self
+                } else {
+                    $generic::new_none()
+                }
+            }
+
+            /// Returns the contained value or `default`.
+            pub const fn unwrap_or(self, default: $t) -> $t {
+                if self.value == SENTINEL {
+                    default
+                } else {
+                    self.value
+                }
+            }
+
+            /// Returns the contained value or computes it from `f`.
+            pub fn unwrap_or_else<F: FnOnce() -> $t>(self, f: F) -> $t {
+                if self.value == SENTINEL {
+                    f()
+                } else {
+                    self.value
+                }
+            }
+
+            /// Returns the contained value.
+            ///
+            /// # Panics
+            ///
+            /// This function panics if this instance contains `None`.
+            pub const fn unwrap(self) -> $t {
+                self.expect("called `unwrap()` on a `None` value")
+            }
+
+            /// Returns the contained value.
+            ///
+            /// # Panics
+            ///
+            /// This function panics with `msg` if this instance contains `None`.
+            pub const fn expect(self, msg: &str) -> $t {
+                if self.value == SENTINEL {
+                    panic!("{}", msg);
+                }
+                self.value
+            }
+
+            /// Transforms this instance into a `Result`, mapping `None` to `Err(err)`.
+            pub fn ok_or<E>(self, err: E) -> Result<$t, E> {
+                if self.value == SENTINEL {
+                    Err(err)
+                } else {
+                    Ok(self.value)
+                }
+            }
+
+            /// Takes the value out of this instance, leaving `None` in its place.
+            pub fn take(&mut self) -> Self {
+                ::core::mem::take(self)
+            }
+
+            /// Replaces the contained value with `value`, returning the previous instance.
+            ///
+            /// # Panics
+            ///
+            /// This function panics if `value` is the sentinel value.
+            #[track_caller]
+            pub fn replace(&mut self, value: $t) -> Self {
+                ::core::mem::replace(self, $generic::checked_new(value))
+            }
+
+            /// Stores `value` in this instance and returns it.
+            ///
+            /// Unlike `Option::insert`, no mutable reference is returned, as writing the sentinel value through it
+            /// would silently turn this instance into `None`.
+            ///
+            /// # Panics
+            ///
+            /// This function panics if `value` is the sentinel value.
+            #[track_caller]
+            pub fn insert(&mut self, value: $t) -> $t {
+                *self = $generic::checked_new(value);
+                value
+            }
+
+            /// Stores the value computed by `f` if this instance contains `None`, then returns the contained value.
+            ///
+            /// # Panics
+            ///
+            /// This function panics if `f` returns the sentinel value.
+            ///
+            /// # Examples
+            ///
+            /// ```rust
+            #[doc = concat!("# use sentinel_int::int_sentinel::", stringify!($name), ";")]
+            #[doc = concat!("let mut sentinel = ", stringify!($name), "::new_none();")]
+            /// assert_eq!(sentinel.get_or_insert_with(|| 42), 42);
+            /// assert_eq!(sentinel.get_or_insert_with(|| 7), 42);
+            /// ```
+            pub fn get_or_insert_with<F: FnOnce() -> $t>(&mut self, f: F) -> $t {
+                if self.value == SENTINEL {
+                    self.insert(f())
+                } else {
+                    self.value
+                }
+            }
+
+            /// Returns the instance containing a value if exactly one of `self` and `other` does, otherwise
+            /// returns `None`.
+            pub const fn xor(self, other: Self) -> Self {
+                match (self.value == SENTINEL, other.value == SENTINEL) {
+                    (false, true) => self,
+                    (true, false) => other,
+                    _ => $generic::new_none(),
+                }
+            }
+
+            /// Returns both contained values if `self` and `other` both contain a value, otherwise returns `None`.
+            pub const fn zip(self, other: Self) -> Option<($t, $t)> {
+                if self.value == SENTINEL || other.value == SENTINEL {
+                    None
+                } else {
+                    Some((self.value, other.value))
+                }
+            }
+
+            #[doc = concat!("Constructs a new `", stringify!($name), "` from a value without checking the sentinel value.")]
+            ///
+            /// # Safety
+            ///
+            #[doc = concat!("If using this function to create an `", stringify!($name),
+                            "`, `sentinel()` will be transformed into a `None` value,")]
+            #[doc = concat!("and any other `", stringify!($t), "` will be mapped to a `Some` of the passed value.")]
+            ///
+            /// # Examples
+            /// ```rust
+            #[doc = concat!("# use sentinel_int::int_sentinel::", stringify!($name), ";")]
+            /// unsafe {
+            #[doc = concat!("    assert_eq!(", stringify!($name), "::unchecked_new(<", stringify!($name),
+                            ">::sentinel()).to_option(), None)")]
+            /// }
+            /// ```
+            ///
+            /// ```rust
+            #[doc = concat!("# use sentinel_int::int_sentinel::", stringify!($name), ";")]
+            /// unsafe {
+            #[doc = concat!("    assert_eq!(", stringify!($name), "::unchecked_new(42", stringify!($t),
+                            ").to_option(), Some(42", stringify!($t), "))")]
+            /// }
+            /// ```
+            pub const unsafe fn unchecked_new(value: $t) -> Self {
+                $generic { value }
+            }
+
+            /// Returns the raw contained value without a check.
+            ///
+            /// # Safety
+            ///
+            /// This method returns `sentinel()` when the instance contains `None`, it returns the contained value
+            /// when the instance contains a different value.
+            ///
+            /// # Examples
+            /// ```rust
+            #[doc = concat!("# use sentinel_int::int_sentinel::", stringify!($name), ";")]
+            /// unsafe {
+            #[doc = concat!("    assert_eq!(", stringify!($name), "::from(Some(42)).", stringify!($to_unchecked), "(), 42);")]
+            /// }
+            /// ```
+            /// ```rust
+            #[doc = concat!("# use sentinel_int::int_sentinel::", stringify!($name), ";")]
+            /// unsafe {
+            #[doc = concat!("    assert_eq!(", stringify!($name), "::from(None).", stringify!($to_unchecked), "(), ",
+                            stringify!($name), "::sentinel());")]
+            /// }
+            /// ```
+            pub const unsafe fn $to_unchecked(&self) -> $t {
+                self.value
+            }
+
+            #[doc = concat!("Reinterprets a slice of raw `", stringify!($t), "` as a slice of `", stringify!($name), "` without copying.")]
+            ///
+            /// Elements equal to `sentinel()` are read as `None`, any other element as a `Some` of its value.
+            ///
+            /// # Examples
+            ///
+            /// ```rust
+            #[doc = concat!("# use sentinel_int::int_sentinel::", stringify!($name), ";")]
+            #[doc = concat!("let raw = [42, ", stringify!($name), "::sentinel()];")]
+            #[doc = concat!("let sentinels = ", stringify!($name), "::from_raw_slice(&raw);")]
+            /// assert_eq!(sentinels[0].to_option(), Some(42));
+            /// assert_eq!(sentinels[1].to_option(), None);
+            /// ```
+            pub const fn from_raw_slice(raw: &[$t]) -> &[Self] {
+                // Sound because the type is `repr(transparent)` and every bit pattern is a valid instance.
+                unsafe { ::core::slice::from_raw_parts(raw.as_ptr() as *const Self, raw.len()) }
+            }
+
+            #[doc = concat!("Reinterprets a mutable slice of raw `", stringify!($t), "` as a mutable slice of `", stringify!($name), "` without copying.")]
+            pub const fn from_raw_slice_mut(raw: &mut [$t]) -> &mut [Self] {
+                // Sound because the type is `repr(transparent)` and every bit pattern is a valid instance.
+                unsafe { ::core::slice::from_raw_parts_mut(raw.as_mut_ptr() as *mut Self, raw.len()) }
+            }
+
+            #[doc = concat!("Reinterprets a slice of `", stringify!($name), "` as a slice of raw `", stringify!($t), "` without copying.")]
+            ///
+            /// `None` elements are read as `sentinel()`.
+            pub const fn as_raw_slice(slice: &[Self]) -> &[$t] {
+                // Sound because the type is `repr(transparent)`.
+                unsafe { ::core::slice::from_raw_parts(slice.as_ptr() as *const $t, slice.len()) }
+            }
+
+            #[doc = concat!("Reinterprets a mutable slice of `", stringify!($name), "` as a mutable slice of raw `", stringify!($t), "` without copying.")]
+            ///
+            /// Writing `sentinel()` to an element of the returned slice turns it into `None`.
+            pub const fn as_raw_slice_mut(slice: &mut [Self]) -> &mut [$t] {
+                // Sound because the type is `repr(transparent)` and every bit pattern is a valid instance.
+                unsafe { ::core::slice::from_raw_parts_mut(slice.as_mut_ptr() as *mut $t, slice.len()) }
+            }
+
+            /// Converts this instance into a type using `OTHER` as its sentinel, keeping its `Option` value.
+            ///
+            /// Returns an error if this instance contains `OTHER` as a `Some`, since it cannot be represented in the
+            /// target type. This is useful to read data that was encoded with a different sentinel, e.g. through
+            /// `from_le_bytes`.
+            ///
+            /// # Examples
+            ///
+            /// ```rust
+            #[doc = concat!("# use sentinel_int::int_sentinel::{", stringify!($name), ", ", stringify!($generic), "};")]
+            #[doc = concat!("let legacy = ", stringify!($generic), "::<0>::from_le_bytes(42", stringify!($t), ".to_le_bytes());")]
+            #[doc = concat!("let sentinel: ", stringify!($name), " = legacy.try_with_sentinel().unwrap();")]
+            /// assert_eq!(sentinel.to_option(), Some(42));
+            /// ```
+            pub const fn try_with_sentinel<const OTHER: $t>(self) -> Result<$generic<OTHER>, SentinelError<$t>> {
+                if self.value == SENTINEL {
+                    Ok($generic::NONE)
+                } else {
+                    $generic::try_new_with_some(self.value)
+                }
+            }
+
+            /// Returns the raw representation of this instance as a byte array in little-endian byte order.
+            ///
+            /// `None` is encoded as `sentinel()`.
+            pub const fn to_le_bytes(self) -> [u8; ::core::mem::size_of::<$t>()] {
+                self.value.to_le_bytes()
+            }
+
+            /// Returns the raw representation of this instance as a byte array in big-endian byte order.
+            ///
+            /// `None` is encoded as `sentinel()`.
+            pub const fn to_be_bytes(self) -> [u8; ::core::mem::size_of::<$t>()] {
+                self.value.to_be_bytes()
+            }
+
+            /// Returns the raw representation of this instance as a byte array in native byte order.
+            ///
+            /// `None` is encoded as `sentinel()`.
+            pub const fn to_ne_bytes(self) -> [u8; ::core::mem::size_of::<$t>()] {
+                self.value.to_ne_bytes()
+            }
+
+            /// Constructs an instance from its raw representation as a byte array in little-endian byte order.
+            ///
+            /// This cannot fail since every raw value is a valid instance, but the bytes are interpreted with this
+            /// type's sentinel: use `try_with_sentinel` to convert bytes that were encoded with another sentinel.
+            ///
+            /// # Examples
+            ///
+            /// ```rust
+            #[doc = concat!("# use sentinel_int::int_sentinel::", stringify!($name), ";")]
+            #[doc = concat!("let sentinel = ", stringify!($name), "::new_with_some(42);")]
+            #[doc = concat!("assert_eq!(", stringify!($name), "::from_le_bytes(sentinel.to_le_bytes()), sentinel);")]
+            /// ```
+            pub const fn from_le_bytes(bytes: [u8; ::core::mem::size_of::<$t>()]) -> Self {
+                $generic { value: $t::from_le_bytes(bytes) }
+            }
+
+            /// Constructs an instance from its raw representation as a byte array in big-endian byte order.
+            ///
+            /// See `from_le_bytes`.
+            pub const fn from_be_bytes(bytes: [u8; ::core::mem::size_of::<$t>()]) -> Self {
+                $generic { value: $t::from_be_bytes(bytes) }
+            }
+
+            /// Constructs an instance from its raw representation as a byte array in native byte order.
+            ///
+            /// See `from_le_bytes`.
+            pub const fn from_ne_bytes(bytes: [u8; ::core::mem::size_of::<$t>()]) -> Self {
+                $generic { value: $t::from_ne_bytes(bytes) }
+            }
+
+            /// Writes the raw representation of each element of `slice` to `buf`, in little-endian byte order.
+            ///
+            /// # Panics
+            ///
+            #[doc = concat!("This function panics if the length of `buf` is not `slice.len() * size_of::<", stringify!($t), ">()`.")]
+            ///
+            /// # Examples
+            ///
+            /// ```rust
+            #[doc = concat!("# use sentinel_int::int_sentinel::", stringify!($name), ";")]
+            #[doc = concat!("let sentinels: [", stringify!($name), "; 2] = [Some(1).into(), None.into()];")]
+            #[doc = concat!("let mut buf = [0u8; 2 * std::mem::size_of::<", stringify!($t), ">()];")]
+            #[doc = concat!(stringify!($name), "::write_le_bytes(&sentinels, &mut buf);")]
+            #[doc = concat!("let mut decoded: [", stringify!($name), "; 2] = Default::default();")]
+            #[doc = concat!(stringify!($name), "::read_le_bytes(&buf, &mut decoded);")]
+            /// assert_eq!(decoded, sentinels);
+            /// ```
+            pub fn write_le_bytes(slice: &[Self], buf: &mut [u8]) {
+                $generic::write_bytes(slice, buf, $t::to_le_bytes)
+            }
+
+            /// Writes the raw representation of each element of `slice` to `buf`, in big-endian byte order.
+            ///
+            /// # Panics
+            ///
+            #[doc = concat!("This function panics if the length of `buf` is not `slice.len() * size_of::<", stringify!($t), ">()`.")]
+            pub fn write_be_bytes(slice: &[Self], buf: &mut [u8]) {
+                $generic::write_bytes(slice, buf, $t::to_be_bytes)
+            }
+
+            /// Writes the raw representation of each element of `slice` to `buf`, in native byte order.
+            ///
+            /// # Panics
+            ///
+            #[doc = concat!("This function panics if the length of `buf` is not `slice.len() * size_of::<", stringify!($t), ">()`.")]
+            pub fn write_ne_bytes(slice: &[Self], buf: &mut [u8]) {
+                $generic::write_bytes(slice, buf, $t::to_ne_bytes)
+            }
+
+            /// Reads each element of `slice` from its raw representation in `buf`, in little-endian byte order.
+            ///
+            /// # Panics
+            ///
+            #[doc = concat!("This function panics if the length of `buf` is not `slice.len() * size_of::<", stringify!($t), ">()`.")]
+            pub fn read_le_bytes(buf: &[u8], slice: &mut [Self]) {
+                $generic::read_bytes(buf, slice, $t::from_le_bytes)
+            }
+
+            /// Reads each element of `slice` from its raw representation in `buf`, in big-endian byte order.
+            ///
+            /// # Panics
+            ///
+            #[doc = concat!("This function panics if the length of `buf` is not `slice.len() * size_of::<", stringify!($t), ">()`.")]
+            pub fn read_be_bytes(buf: &[u8], slice: &mut [Self]) {
+                $generic::read_bytes(buf, slice, $t::from_be_bytes)
+            }
+
+            /// Reads each element of `slice` from its raw representation in `buf`, in native byte order.
+            ///
+            /// # Panics
+            ///
+            #[doc = concat!("This function panics if the length of `buf` is not `slice.len() * size_of::<", stringify!($t), ">()`.")]
+            pub fn read_ne_bytes(buf: &[u8], slice: &mut [Self]) {
+                $generic::read_bytes(buf, slice, $t::from_ne_bytes)
+            }
+
+            /// Returns an adapter displaying this instance with `null` as the representation of `None`.
+            ///
+            /// # Examples
+            ///
+            /// ```rust
+            #[doc = concat!("# use sentinel_int::int_sentinel::", stringify!($name), ";")]
+            #[doc = concat!("let sentinel = ", stringify!($name), "::new_none();")]
+            /// assert_eq!(format!("[{:>4}]", sentinel.display_with("-")), "[   -]");
+            /// ```
+            pub fn display_with<'a>(&self, null: &'a str) -> DisplayWith<'a, $t> {
+                DisplayWith::new(self.to_option(), null)
+            }
+
+            /// Parses `s` as `FromStr` does, but accepting the strings of `null_tokens` as `None` instead of
+            /// the default `NULL_TOKENS`. The null tokens are compared ASCII case-insensitively.
+            ///
+            /// # Examples
+            ///
+            /// ```rust
+            #[doc = concat!("# use sentinel_int::int_sentinel::", stringify!($name), ";")]
+            #[doc = concat!("let sentinel = ", stringify!($name), "::parse_with_nulls(\"NA\", &[\"NA\"]).unwrap();")]
+            /// assert_eq!(sentinel.to_option(), None);
+            #[doc = concat!("assert!(", stringify!($name), "::parse_with_nulls(\"null\", &[\"NA\"]).is_err());")]
+            /// ```
+            pub fn parse_with_nulls(s: &str, null_tokens: &[&str]) -> Result<Self, ParseSentinelError<$t>> {
+                if text::is_null_token(s, null_tokens) {
+                    return Ok($generic::new_none());
+                }
+                let value = match text::strip_hex_prefix(s)? {
+                    Some(digits) => $t::from_str_radix(digits, 16)?,
+                    None => s.parse()?,
+                };
+                Ok($generic::try_new_with_some(value)?)
+            }
+
+            /// Same as `new_with_some`, but reporting the offending value in the panic message, which is only
+            /// possible outside of a `const` context.
+            #[track_caller]
+            fn checked_new(value: $t) -> Self {
+                match $generic::try_new_with_some(value) {
+                    Ok(sentinel) => sentinel,
+                    Err(error) => panic!("{}", error),
+                }
+            }
+
+            fn write_bytes(slice: &[Self], buf: &mut [u8], to_bytes: fn($t) -> [u8; ::core::mem::size_of::<$t>()]) {
+                const SIZE: usize = ::core::mem::size_of::<$t>();
+                assert_eq!(buf.len(), slice.len() * SIZE, "buffer length does not match the slice length");
+                for (chunk, &raw) in buf.chunks_exact_mut(SIZE).zip($generic::as_raw_slice(slice)) {
+                    chunk.copy_from_slice(&to_bytes(raw));
+                }
+            }
+
+            fn read_bytes(buf: &[u8], slice: &mut [Self], from_bytes: fn([u8; ::core::mem::size_of::<$t>()]) -> $t) {
+                const SIZE: usize = ::core::mem::size_of::<$t>();
+                assert_eq!(buf.len(), slice.len() * SIZE, "buffer length does not match the slice length");
+                for (chunk, raw) in buf.chunks_exact(SIZE).zip($generic::as_raw_slice_mut(slice)) {
+                    let mut bytes = [0; SIZE];
+                    bytes.copy_from_slice(chunk);
+                    *raw = from_bytes(bytes);
+                }
+            }
+        }
+
+        impl<const SENTINEL: $t> Default for $generic<SENTINEL> {
+            fn default() -> Self {
+                $generic::new_none()
+            }
+        }
+
+        impl<const SENTINEL: $t> PartialOrd for $generic<SENTINEL> {
+            fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+
+        impl<const SENTINEL: $t> Ord for $generic<SENTINEL> {
+            fn cmp(&self, other: &Self) -> Ordering {
+                self.to_option().cmp(&other.to_option())
+            }
+        }
+
+        impl<const SENTINEL: $t> fmt::Display for $generic<SENTINEL> {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                fmt::Display::fmt(&self.display_with(text::DEFAULT_NULL), f)
+            }
+        }
+
+        impl<const SENTINEL: $t> FromStr for $generic<SENTINEL> {
+            type Err = ParseSentinelError<$t>;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                $generic::parse_with_nulls(s, text::NULL_TOKENS)
+            }
+        }
+
+        impl<const SENTINEL: $t> From<Option<$t>> for $generic<SENTINEL> {
+            #[track_caller]
+            fn from(option: Option<$t>) -> Self {
+                match option {
+                    Some(value) => $generic::checked_new(value),
+                    None => $generic::new_none()
+                }
+            }
+        }
+
+        impl<const SENTINEL: $t> TryFrom<$t> for $generic<SENTINEL> {
+            type Error = SentinelError<$t>;
+
+            fn try_from(value: $t) -> Result<Self, Self::Error> {
+                $generic::try_new_with_some(value)
+            }
+        }
+
+        impl<const SENTINEL: $t> From<$generic<SENTINEL>> for Option<$t> {
+            fn from(sentinel : $generic<SENTINEL>) -> Self {
+                sentinel.to_option()
+            }
+        }
+    };
+}
+
+int_sentinel!(IntSentinelU8, IntSentinelU8With, u8, to_u8_unchecked, MAX);
+int_sentinel!(IntSentinelU16, IntSentinelU16With, u16, to_u16_unchecked, MAX);
+int_sentinel!(IntSentinelU32, IntSentinelU32With, u32, to_u32_unchecked, MAX);
+int_sentinel!(IntSentinel, IntSentinelWith, u64, to_u64_unchecked, MAX);
+int_sentinel!(IntSentinelU128, IntSentinelU128With, u128, to_u128_unchecked, MAX);
+int_sentinel!(IntSentinelUsize, IntSentinelUsizeWith, usize, to_usize_unchecked, MAX);
+
+int_sentinel!(IntSentinelI8, IntSentinelI8With, i8, to_i8_unchecked, MIN);
+int_sentinel!(IntSentinelI16, IntSentinelI16With, i16, to_i16_unchecked, MIN);
+int_sentinel!(IntSentinelI32, IntSentinelI32With, i32, to_i32_unchecked, MIN);
+int_sentinel!(IntSentinelI64, IntSentinelI64With, i64, to_i64_unchecked, MIN);
+int_sentinel!(IntSentinelI128, IntSentinelI128With, i128, to_i128_unchecked, MIN);
+int_sentinel!(IntSentinelIsize, IntSentinelIsizeWith, isize, to_isize_unchecked, MIN);
+
+#[cfg(test)]
+mod tests {
+    use std::vec::Vec;
+
+    macro_rules! int_sentinel_tests {
+        ($module:ident, $name:ident, $generic:ident, $t:ident, $to_unchecked:ident, $sentinel:ident) => {
+            mod $module {
+                use std::convert::TryFrom;
+                use std::string::ToString;
+                use std::vec::Vec;
+
+                use int_sentinel::{$name, $generic};
+                use text::ParseSentinelError;
+
+                #[test]
+                fn unsafe_value() {
+                    let x = 42;
+                    unsafe {
+                        let sentinel = $name::unchecked_new(x);
+                        assert_eq!(sentinel.$to_unchecked(), x);
+                    }
+                }
+
+                #[test]
+                fn some_value() {
+                    let x = 42;
+                    let sentinel = $name::new_with_some(x);
+                    assert!(sentinel.to_option().is_some());
+                    let value = sentinel.to_option().unwrap();
+                    assert_eq!(value, x);
+                }
+
+                #[test]
+                fn none_value() {
+                    let sentinel = $name::new_none();
+                    assert!(sentinel.to_option().is_none());
+                }
+
+                #[test]
+                fn using_from_some() {
+                    let with_value = Some(42 as $t);
+                    let sentinel = $name::from(with_value);
+                    let from_sentinel = Option::<$t>::from(sentinel);
+                    assert_eq!(from_sentinel, with_value);
+                }
+
+                #[test]
+                fn using_from_none() {
+                    let sentinel = $name::from(None);
+                    let from_sentinel = Option::<$t>::from(sentinel);
+                    assert_eq!(from_sentinel, None);
+                }
+
+                #[test]
+                fn max_value() {
+                    let sentinel = $name::new_with_some($name::max_value());
+                    assert_eq!(sentinel.to_option(), Some($name::max_value()));
+                }
+
+                #[test]
+                fn min_value() {
+                    let sentinel = $name::new_with_some($name::min_value());
+                    assert_eq!(sentinel.to_option(), Some($name::min_value()));
+                }
+
+                #[test]
+                fn try_some_value() {
+                    let sentinel = $name::try_new_with_some(42).unwrap();
+                    assert_eq!(sentinel.to_option(), Some(42));
+                    let sentinel = $name::try_from(42 as $t).unwrap();
+                    assert_eq!(sentinel.to_option(), Some(42));
+                }
+
+                #[should_panic(expected = "is the sentinel value")]
+                #[test]
+                fn try_from_illegal_option() {
+                    // `TryFrom<Option<_>>` is the blanket implementation derived from `From`, which panics:
+                    // `try_from_option` is the fallible conversion.
+                    let _ = $name::try_from(Some($t::$sentinel));
+                }
+
+                #[test]
+                fn try_illegal_value() {
+                    let error = $name::try_new_with_some($t::$sentinel).unwrap_err();
+                    assert_eq!(error.value(), $t::$sentinel);
+                    let error = $name::try_from($t::$sentinel).unwrap_err();
+                    assert_eq!(error.value(), $t::$sentinel);
+                }
+
+                #[test]
+                fn try_from_option() {
+                    assert_eq!($name::try_from_option(None).unwrap().to_option(), None);
+                    assert_eq!($name::try_from_option(Some(42)).unwrap().to_option(), Some(42));
+                    let error = $name::try_from_option(Some($t::$sentinel)).unwrap_err();
+                    assert_eq!(error.value(), $t::$sentinel);
+                }
+
+                #[should_panic]
+                #[test]
+                fn some_illegal_value() {
+                    $name::new_with_some($t::$sentinel);
+                }
+
+                #[should_panic(expected = "is the sentinel value")]
+                #[test]
+                fn using_from_illegal_value() {
+                    let with_value = Some($t::$sentinel);
+                    let _ = $name::from(with_value);
+                }
+
+                #[test]
+                fn layout() {
+                    use std::mem::{align_of, size_of};
+                    assert_eq!(size_of::<$name>(), size_of::<$t>());
+                    assert_eq!(align_of::<$name>(), align_of::<$t>());
+                    // Every bit pattern is a valid instance, so that `Option` cannot use a niche.
+                    assert_eq!(size_of::<Option<$name>>(), size_of::<Option<$t>>());
+                }
+
+                #[test]
+                fn raw_slices() {
+                    let mut raw = [1, $t::$sentinel, 3];
+                    {
+                        let sentinels = $name::from_raw_slice(&raw);
+                        let options: Vec<_> = sentinels.iter().map($name::to_option).collect();
+                        assert_eq!(options, vec![Some(1), None, Some(3)]);
+                        assert_eq!($name::as_raw_slice(sentinels), &[1, $t::$sentinel, 3]);
+                    }
+                    {
+                        let sentinels = $name::from_raw_slice_mut(&mut raw);
+                        sentinels[0].take();
+                        sentinels[1].insert(2);
+                        $name::as_raw_slice_mut(sentinels)[2] = $t::$sentinel;
+                    }
+                    assert_eq!(raw, [$t::$sentinel, 2, $t::$sentinel]);
+                }
+
+                #[test]
+                fn bytes() {
+                    let some = $name::new_with_some(42);
+                    let none = $name::new_none();
+                    assert_eq!(some.to_le_bytes(), (42 as $t).to_le_bytes());
+                    assert_eq!(none.to_be_bytes(), $t::$sentinel.to_be_bytes());
+                    assert_eq!($name::from_le_bytes(some.to_le_bytes()), some);
+                    assert_eq!($name::from_be_bytes(some.to_be_bytes()), some);
+                    assert_eq!($name::from_ne_bytes(none.to_ne_bytes()), none);
+                }
+
+                #[test]
+                fn slice_bytes() {
+                    const SIZE: usize = ::std::mem::size_of::<$t>();
+                    let sentinels: [$name; 3] = [Some(1).into(), None.into(), Some(2).into()];
+                    let mut buf = [0u8; 3 * SIZE];
+                    let mut decoded: [$name; 3] = Default::default();
+                    $name::write_le_bytes(&sentinels, &mut buf);
+                    assert_eq!(&buf[..SIZE], &(1 as $t).to_le_bytes());
+                    $name::read_le_bytes(&buf, &mut decoded);
+                    assert_eq!(decoded, sentinels);
+                    $name::write_be_bytes(&sentinels, &mut buf);
+                    assert_eq!(&buf[2 * SIZE..], &(2 as $t).to_be_bytes());
+                    $name::read_be_bytes(&buf, &mut decoded);
+                    assert_eq!(decoded, sentinels);
+                    $name::write_ne_bytes(&sentinels, &mut buf);
+                    $name::read_ne_bytes(&buf, &mut decoded);
+                    assert_eq!(decoded, sentinels);
+                }
+
+                #[should_panic]
+                #[test]
+                fn slice_bytes_length_mismatch() {
+                    let sentinels: [$name; 2] = Default::default();
+                    $name::write_le_bytes(&sentinels, &mut [0u8; 3]);
+                }
+
+                #[test]
+                fn display() {
+                    let some = $name::new_with_some(42);
+                    let none = $name::new_none();
+                    assert_eq!(some.to_string(), "42");
+                    assert_eq!(none.to_string(), "null");
+                    assert_eq!(format!("{:>3}|{:<5}|", some, none), " 42|null |");
+                    assert_eq!(none.display_with("").to_string(), "");
+                    assert_eq!(some.display_with("").to_string(), "42");
+                }
+
+                #[test]
+                fn from_str() {
+                    for null in &["", "null", "NULL", "None", "-"] {
+                        assert_eq!(null.parse::<$name>().unwrap(), $name::new_none());
+                    }
+                    assert_eq!("42".parse::<$name>().unwrap().to_option(), Some(42));
+                    assert_eq!("0x2a".parse::<$name>().unwrap().to_option(), Some(42));
+                    assert_eq!("0X2A".parse::<$name>().unwrap().to_option(), Some(42));
+                    assert!("0x-5".parse::<$name>().is_err());
+                    assert!("0x+5".parse::<$name>().is_err());
+                    let value = $name::new_with_some($name::max_value());
+                    assert_eq!(value.to_string().parse::<$name>().unwrap(), value);
+                    match "nil".parse::<$name>() {
+                        Err(ParseSentinelError::Int(_)) => {}
+                        other => panic!("unexpected result: {:?}", other),
+                    }
+                    match $t::$sentinel.to_string().parse::<$name>() {
+                        Err(ParseSentinelError::Sentinel(error)) => assert_eq!(error.value(), $t::$sentinel),
+                        other => panic!("unexpected result: {:?}", other),
+                    }
+                }
+
+                #[test]
+                fn parse_with_nulls() {
+                    assert_eq!($name::parse_with_nulls("n/a", &["N/A"]).unwrap(), $name::new_none());
+                    assert!($name::parse_with_nulls("", &["N/A"]).is_err());
+                    assert_eq!($name::parse_with_nulls("7", &[]).unwrap().to_option(), Some(7));
+                }
+
+                #[test]
+                fn try_with_sentinel() {
+                    let legacy = $generic::<0>::from_le_bytes((42 as $t).to_le_bytes());
+                    let sentinel: $name = legacy.try_with_sentinel().unwrap();
+                    assert_eq!(sentinel.to_option(), Some(42));
+                    let legacy = $generic::<0>::from_le_bytes((0 as $t).to_le_bytes());
+                    let sentinel: $name = legacy.try_with_sentinel().unwrap();
+                    assert_eq!(sentinel.to_option(), None);
+                    let legacy = $generic::<0>::from_le_bytes($t::$sentinel.to_le_bytes());
+                    let error = legacy.try_with_sentinel::<{ $t::$sentinel }>().unwrap_err();
+                    assert_eq!(error.value(), $t::$sentinel);
+                }
+            }
+        };
+    }
+
+    int_sentinel_tests!(u8_sentinel, IntSentinelU8, IntSentinelU8With, u8, to_u8_unchecked, MAX);
+    int_sentinel_tests!(u16_sentinel, IntSentinelU16, IntSentinelU16With, u16, to_u16_unchecked, MAX);
+    int_sentinel_tests!(u32_sentinel, IntSentinelU32, IntSentinelU32With, u32, to_u32_unchecked, MAX);
+    int_sentinel_tests!(u64_sentinel, IntSentinel, IntSentinelWith, u64, to_u64_unchecked, MAX);
+    int_sentinel_tests!(u128_sentinel, IntSentinelU128, IntSentinelU128With, u128, to_u128_unchecked, MAX);
+    int_sentinel_tests!(usize_sentinel, IntSentinelUsize, IntSentinelUsizeWith, usize, to_usize_unchecked, MAX);
+
+    int_sentinel_tests!(i8_sentinel, IntSentinelI8, IntSentinelI8With, i8, to_i8_unchecked, MIN);
+    int_sentinel_tests!(i16_sentinel, IntSentinelI16, IntSentinelI16With, i16, to_i16_unchecked, MIN);
+    int_sentinel_tests!(i32_sentinel, IntSentinelI32, IntSentinelI32With, i32, to_i32_unchecked, MIN);
+    int_sentinel_tests!(i64_sentinel, IntSentinelI64, IntSentinelI64With, i64, to_i64_unchecked, MIN);
+    int_sentinel_tests!(i128_sentinel, IntSentinelI128, IntSentinelI128With, i128, to_i128_unchecked, MIN);
+    int_sentinel_tests!(isize_sentinel, IntSentinelIsize, IntSentinelIsizeWith, isize, to_isize_unchecked, MIN);
+
+    #[test]
+    fn signed_range_is_symmetric() {
+        use int_sentinel::{IntSentinelI8, IntSentinelI64};
+        assert_eq!(IntSentinelI8::min_value(), -IntSentinelI8::max_value());
+        assert_eq!(IntSentinelI64::min_value(), -IntSentinelI64::max_value());
+    }
+
+    #[test]
+    fn negative_value() {
+        use int_sentinel::IntSentinelI64;
+        let sentinel = IntSentinelI64::from(Some(-42));
+        assert_eq!(Option::<i64>::from(sentinel), Some(-42));
+    }
+
+    #[test]
+    fn zero_sentinel() {
+        use int_sentinel::IntSentinelWith;
+        let none = IntSentinelWith::<0>::new_none();
+        assert_eq!(unsafe { none.to_u64_unchecked() }, 0);
+        assert_eq!(IntSentinelWith::<0>::from(Some(u64::MAX)).to_option(), Some(u64::MAX));
+        assert_eq!(IntSentinelWith::<0>::max_value(), u64::MAX);
+        assert_eq!(IntSentinelWith::<0>::min_value(), 1);
+    }
+
+    #[test]
+    fn middle_sentinel() {
+        use int_sentinel::IntSentinelWith;
+        type Legacy = IntSentinelWith<0xFFFF_FFFF>;
+        assert!(!Legacy::is_valid(0xFFFF_FFFF));
+        assert!(Legacy::is_valid(0xFFFF_FFFE));
+        assert!(Legacy::is_valid(0x1_0000_0000));
+        assert_eq!(Legacy::max_value(), u64::MAX);
+        assert_eq!(Legacy::min_value(), 0);
+        assert_eq!(Legacy::from(Some(0x1_0000_0000)).to_option(), Some(0x1_0000_0000));
+        assert_eq!(Legacy::from(None).to_option(), None);
+    }
+
+    #[should_panic]
+    #[test]
+    fn middle_sentinel_illegal_value() {
+        use int_sentinel::IntSentinelWith;
+        IntSentinelWith::<0xFFFF_FFFF>::new_with_some(0xFFFF_FFFF);
+    }
+
+    #[test]
+    fn combinators() {
+        use int_sentinel::IntSentinel;
+        let some = IntSentinel::new_with_some(21);
+        let none = IntSentinel::new_none();
+        assert!(some.is_some() && !some.is_none());
+        assert!(none.is_none() && !none.is_some());
+        assert_eq!(IntSentinel::new_with_some(21).map(|x| x * 2).to_option(), Some(42));
+        assert_eq!(IntSentinel::new_none().map(|x| x * 2).to_option(), None);
+        assert_eq!(IntSentinel::new_with_some(21).and_then(|x| IntSentinel::from(x.checked_sub(1))).to_option(), Some(20));
+        assert_eq!(IntSentinel::new_with_some(0).and_then(|x| IntSentinel::from(x.checked_sub(1))).to_option(), None);
+        assert_eq!(IntSentinel::new_with_some(21).filter(|x| x % 2 == 1).to_option(), Some(21));
+        assert_eq!(IntSentinel::new_with_some(20).filter(|x| x % 2 == 1).to_option(), None);
+        assert_eq!(IntSentinel::new_none().unwrap_or(7), 7);
+        assert_eq!(IntSentinel::new_with_some(21).unwrap_or_else(|| 7), 21);
+        assert_eq!(IntSentinel::new_with_some(21).expect("some"), 21);
+        assert_eq!(IntSentinel::new_none().ok_or("none"), Err("none"));
+        assert_eq!(IntSentinel::new_with_some(21).ok_or("none"), Ok(21));
+    }
+
+    #[test]
+    fn mutating_combinators() {
+        use int_sentinel::IntSentinel;
+        let mut sentinel = IntSentinel::new_with_some(21);
+        assert_eq!(sentinel.take().to_option(), Some(21));
+        assert!(sentinel.is_none());
+        assert_eq!(sentinel.replace(42).to_option(), None);
+        assert_eq!(sentinel.insert(43), 43);
+        assert_eq!(sentinel.get_or_insert_with(|| 0), 43);
+        assert_eq!(sentinel.to_option(), Some(43));
+    }
+
+    #[test]
+    fn binary_combinators() {
+        use int_sentinel::IntSentinel;
+        let some = |x| -> IntSentinel { IntSentinel::new_with_some(x) };
+        let none = || -> IntSentinel { IntSentinel::new_none() };
+        assert_eq!(some(1).xor(none()).to_option(), Some(1));
+        assert_eq!(none().xor(some(2)).to_option(), Some(2));
+        assert_eq!(some(1).xor(some(2)).to_option(), None);
+        assert_eq!(none().xor(none()).to_option(), None);
+        assert_eq!(some(1).zip(some(2)), Some((1, 2)));
+        assert_eq!(some(1).zip(none()), None);
+    }
+
+    #[should_panic]
+    #[test]
+    fn map_to_sentinel() {
+        use int_sentinel::IntSentinelI64;
+        let _ = IntSentinelI64::new_with_some(-1).map(|x| x * i64::MAX - 1);
+    }
+
+    #[should_panic]
+    #[test]
+    fn unwrap_none() {
+        use int_sentinel::IntSentinel;
+        IntSentinel::new_none().unwrap();
+    }
+
+    #[test]
+    fn default_is_none() {
+        use int_sentinel::IntSentinel;
+        let sentinels: [IntSentinel; 4] = Default::default();
+        assert!(sentinels.iter().all(|sentinel| sentinel.is_none()));
+    }
+
+    #[test]
+    fn hash_set() {
+        use std::collections::HashSet;
+        use int_sentinel::IntSentinel;
+        let set: HashSet<IntSentinel> = [Some(1), None, Some(1), None, Some(2)].iter().map(|&x| IntSentinel::from(x)).collect();
+        assert_eq!(set.len(), 3);
+        assert!(set.contains(&IntSentinel::new_none()));
+    }
+
+    #[test]
+    fn ordering_matches_option() {
+        use int_sentinel::{IntSentinel, IntSentinelI64, IntSentinelWith};
+        let values = [None, Some(0), Some(1), Some(42), Some(u64::MAX - 1)];
+        for &a in values.iter() {
+            for &b in values.iter() {
+                let (x, y): (IntSentinel, IntSentinel) = (a.into(), b.into());
+                assert_eq!(x.cmp(&y), x.to_option().cmp(&y.to_option()));
+                assert_eq!(x == y, a == b);
+                let (x, y): (IntSentinelWith<0>, IntSentinelWith<0>) = (
+                    a.map(|v| v + 1).into(),
+                    b.map(|v| v + 1).into(),
+                );
+                assert_eq!(x.cmp(&y), x.to_option().cmp(&y.to_option()));
+            }
+        }
+        let values = [None, Some(i64::MIN + 1), Some(-1), Some(0), Some(i64::MAX)];
+        for &a in values.iter() {
+            for &b in values.iter() {
+                let (x, y): (IntSentinelI64, IntSentinelI64) = (a.into(), b.into());
+                assert_eq!(x.cmp(&y), x.to_option().cmp(&y.to_option()));
+            }
+        }
+    }
+
+    #[test]
+    fn none_sorts_first() {
+        use int_sentinel::IntSentinel;
+        let mut sentinels: Vec<IntSentinel> = vec![Some(3).into(), None.into(), Some(1).into()];
+        sentinels.sort();
+        let options: Vec<_> = sentinels.iter().map(IntSentinel::to_option).collect();
+        assert_eq!(options, vec![None, Some(1), Some(3)]);
+    }
+
+    #[test]
+    fn const_table() {
+        use int_sentinel::{IntSentinel, IntSentinelWith};
+        const TABLE: [IntSentinel; 3] = [
+            IntSentinel::NONE,
+            IntSentinel::new_with_some(1),
+            IntSentinel::new_with_some(IntSentinel::MAX),
+        ];
+        const VALUES: [Option<u64>; 3] = [TABLE[0].to_option(), TABLE[1].to_option(), TABLE[2].to_option()];
+        const FALLIBLE: Option<u64> = match IntSentinelWith::<0>::try_new_with_some(0) {
+            Ok(_) => None,
+            Err(error) => Some(error.value()),
+        };
+        assert_eq!(VALUES, [None, Some(1), Some(u64::MAX - 1)]);
+        assert_eq!(FALLIBLE, Some(0));
+        assert_eq!(IntSentinel::SENTINEL, u64::MAX);
+        assert_eq!(IntSentinelWith::<0>::MIN, 1);
+    }
+}
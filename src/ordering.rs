@@ -0,0 +1,250 @@
+//! Ordering policies for the sentinel integers.
+//!
+//! The `Ord` implementation of the sentinel integers matches `Option`, i.e. `None` sorts first.
+//! The policies of this module allow choosing a different ordering at a given call site:
+//!
+//! - `NoneFirst` orders like `Option` (and like the `Ord` implementation),
+//! - `NoneLast` sorts `None` after every value. With the default sentinel of the unsigned types, this is the raw order
+//!   and is compiled to a plain integer comparison,
+//! - `NoneIncomparable` follows SQL semantics, where `None` cannot be compared with anything, not even with `None`.
+//!
+//! # Examples
+//!
+//! ```rust
+//! # use sentinel_int::int_sentinel::IntSentinel;
+//! use sentinel_int::ordering::{NoneLast, TotalOrderingPolicy};
+//! let mut sentinels: Vec<IntSentinel> = vec![None.into(), Some(2).into(), Some(1).into()];
+//! NoneLast::sort_unstable(&mut sentinels);
+//! let options: Vec<_> = sentinels.iter().map(IntSentinel::to_option).collect();
+//! assert_eq!(options, vec![Some(1), Some(2), None]);
+//! ```
+
+use core::cmp::Ordering;
+use core::fmt;
+#[cfg(feature = "std")]
+use std::error::Error;
+
+use int_sentinel::*;
+
+/// A policy describing how to compare values of type `T` that may contain `None`.
+///
+/// An element is considered incomparable if it is not comparable with itself, in which case
+/// `partial_cmp` must return `None` whenever this element is one of the operands.
+pub trait OrderingPolicy<T> {
+    /// Compares `a` and `b` according to this policy, returns `None` if they are incomparable.
+    fn partial_cmp(a: &T, b: &T) -> Option<Ordering>;
+
+    /// Sorts `slice` according to this policy, or returns the index of the first incomparable element.
+    ///
+    /// The slice is left untouched when an error is returned. This sort is unstable, and does not allocate.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use sentinel_int::int_sentinel::IntSentinel;
+    /// use sentinel_int::ordering::{NoneIncomparable, OrderingPolicy};
+    /// let mut sentinels: Vec<IntSentinel> = vec![Some(2).into(), None.into(), Some(1).into()];
+    /// assert_eq!(NoneIncomparable::try_sort(&mut sentinels).unwrap_err().index(), 1);
+    /// ```
+    fn try_sort(slice: &mut [T]) -> Result<(), IncomparableError> {
+        if let Some(index) = slice.iter().position(|x| Self::partial_cmp(x, x).is_none()) {
+            return Err(IncomparableError { index });
+        }
+        slice.sort_unstable_by(|a, b| Self::partial_cmp(a, b).unwrap());
+        Ok(())
+    }
+}
+
+/// A policy that defines a total order over the values of type `T`.
+pub trait TotalOrderingPolicy<T>: OrderingPolicy<T> {
+    /// Compares `a` and `b` according to this policy.
+    fn cmp(a: &T, b: &T) -> Ordering;
+
+    /// Sorts `slice` according to this policy. This sort is stable.
+    #[cfg(feature = "std")]
+    fn sort(slice: &mut [T]) {
+        slice.sort_by(Self::cmp)
+    }
+
+    /// Sorts `slice` according to this policy. This sort is unstable.
+    fn sort_unstable(slice: &mut [T]) {
+        slice.sort_unstable_by(Self::cmp)
+    }
+
+    /// Returns `true` if `slice` is sorted according to this policy.
+    fn is_sorted(slice: &[T]) -> bool {
+        slice.windows(2).all(|pair| Self::cmp(&pair[0], &pair[1]) != Ordering::Greater)
+    }
+
+    /// Binary searches `slice`, that must be sorted according to this policy, for `value`.
+    ///
+    /// See `slice::binary_search` for the meaning of the result.
+    fn binary_search(slice: &[T], value: &T) -> Result<usize, usize> {
+        slice.binary_search_by(|probe| Self::cmp(probe, value))
+    }
+}
+
+/// Orders `None` before any value, like `Option`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct NoneFirst;
+
+/// Orders `None` after any value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct NoneLast;
+
+/// Considers `None` incomparable with any value, including `None`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct NoneIncomparable;
+
+/// The error returned when trying to sort a slice containing an incomparable element.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct IncomparableError {
+    index: usize,
+}
+
+impl IncomparableError {
+    /// Returns the index of the first incomparable element of the slice.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+}
+
+impl fmt::Display for IncomparableError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Incomparable element at index {}.", self.index)
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for IncomparableError {}
+
+macro_rules! ordering_policies {
+    ($($name:ident => $t:ident, $to_unchecked:ident;)*) => {
+        $(
+            impl<const SENTINEL: $t> OrderingPolicy<$name<SENTINEL>> for NoneFirst {
+                fn partial_cmp(a: &$name<SENTINEL>, b: &$name<SENTINEL>) -> Option<Ordering> {
+                    Some(a.cmp(b))
+                }
+            }
+
+            impl<const SENTINEL: $t> TotalOrderingPolicy<$name<SENTINEL>> for NoneFirst {
+                fn cmp(a: &$name<SENTINEL>, b: &$name<SENTINEL>) -> Ordering {
+                    a.cmp(b)
+                }
+            }
+
+            impl<const SENTINEL: $t> OrderingPolicy<$name<SENTINEL>> for NoneLast {
+                fn partial_cmp(a: &$name<SENTINEL>, b: &$name<SENTINEL>) -> Option<Ordering> {
+                    Some(<NoneLast as TotalOrderingPolicy<_>>::cmp(a, b))
+                }
+            }
+
+            impl<const SENTINEL: $t> TotalOrderingPolicy<$name<SENTINEL>> for NoneLast {
+                fn cmp(a: &$name<SENTINEL>, b: &$name<SENTINEL>) -> Ordering {
+                    // The raw values are only compared, so that reading the sentinel is harmless.
+                    let (a, b) = unsafe { (a.$to_unchecked(), b.$to_unchecked()) };
+                    if SENTINEL == $t::MAX {
+                        a.cmp(&b)
+                    } else {
+                        (a == SENTINEL, a).cmp(&(b == SENTINEL, b))
+                    }
+                }
+            }
+
+            impl<const SENTINEL: $t> OrderingPolicy<$name<SENTINEL>> for NoneIncomparable {
+                fn partial_cmp(a: &$name<SENTINEL>, b: &$name<SENTINEL>) -> Option<Ordering> {
+                    match (a.to_option(), b.to_option()) {
+                        (Some(a), Some(b)) => Some(a.cmp(&b)),
+                        _ => None,
+                    }
+                }
+            }
+        )*
+    };
+}
+
+ordering_policies! {
+    IntSentinelU8With => u8, to_u8_unchecked;
+    IntSentinelU16With => u16, to_u16_unchecked;
+    IntSentinelU32With => u32, to_u32_unchecked;
+    IntSentinelWith => u64, to_u64_unchecked;
+    IntSentinelU128With => u128, to_u128_unchecked;
+    IntSentinelUsizeWith => usize, to_usize_unchecked;
+    IntSentinelI8With => i8, to_i8_unchecked;
+    IntSentinelI16With => i16, to_i16_unchecked;
+    IntSentinelI32With => i32, to_i32_unchecked;
+    IntSentinelI64With => i64, to_i64_unchecked;
+    IntSentinelI128With => i128, to_i128_unchecked;
+    IntSentinelIsizeWith => isize, to_isize_unchecked;
+}
+
+#[cfg(test)]
+mod tests {
+    use std::vec::Vec;
+
+    use super::*;
+
+    fn sentinels<const SENTINEL: u64>(options: &[Option<u64>]) -> Vec<IntSentinelWith<SENTINEL>> {
+        options.iter().map(|&option| IntSentinelWith::from(option)).collect()
+    }
+
+    fn options<const SENTINEL: u64>(sentinels: &[IntSentinelWith<SENTINEL>]) -> Vec<Option<u64>> {
+        sentinels.iter().map(IntSentinelWith::to_option).collect()
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn none_first() {
+        let mut values = sentinels::<{ u64::MAX }>(&[Some(3), None, Some(1), None]);
+        NoneFirst::sort(&mut values);
+        assert_eq!(options(&values), vec![None, None, Some(1), Some(3)]);
+        assert!(NoneFirst::is_sorted(&values));
+        assert_eq!(NoneFirst::binary_search(&values, &Some(3).into()), Ok(3));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn none_last() {
+        let mut values = sentinels::<{ u64::MAX }>(&[Some(3), None, Some(1), None]);
+        NoneLast::sort(&mut values);
+        assert_eq!(options(&values), vec![Some(1), Some(3), None, None]);
+        assert!(NoneLast::is_sorted(&values));
+        assert!(!NoneFirst::is_sorted(&values));
+        assert_eq!(NoneLast::binary_search(&values, &Some(2).into()), Err(1));
+    }
+
+    #[test]
+    fn none_last_custom_sentinel() {
+        let mut values = sentinels::<5>(&[Some(7), None, Some(u64::MAX), Some(1)]);
+        NoneLast::sort_unstable(&mut values);
+        assert_eq!(options(&values), vec![Some(1), Some(7), Some(u64::MAX), None]);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn none_last_signed() {
+        let mut values: Vec<IntSentinelI64> = vec![None.into(), Some(-1).into(), Some(i64::MIN + 1).into()];
+        NoneLast::sort(&mut values);
+        let options: Vec<_> = values.iter().map(IntSentinelI64::to_option).collect();
+        assert_eq!(options, vec![Some(i64::MIN + 1), Some(-1), None]);
+    }
+
+    #[test]
+    fn none_incomparable() {
+        let none = IntSentinel::new_none();
+        assert_eq!(NoneIncomparable::partial_cmp(&none, &none), None);
+        let mut values = sentinels::<{ u64::MAX }>(&[Some(3), Some(1)]);
+        assert!(NoneIncomparable::try_sort(&mut values).is_ok());
+        assert_eq!(options(&values), vec![Some(1), Some(3)]);
+        let mut values = sentinels::<{ u64::MAX }>(&[Some(3), Some(1), None]);
+        assert_eq!(NoneIncomparable::try_sort(&mut values), Err(IncomparableError { index: 2 }));
+        assert_eq!(options(&values), vec![Some(3), Some(1), None]);
+    }
+
+    #[test]
+    fn total_policies_try_sort() {
+        let mut values = sentinels::<{ u64::MAX }>(&[Some(3), None]);
+        assert!(<NoneLast as OrderingPolicy<_>>::try_sort(&mut values).is_ok());
+        assert_eq!(options(&values), vec![Some(3), None]);
+    }
+}
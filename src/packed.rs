@@ -0,0 +1,556 @@
+//! Sentinel integers of odd widths, stored as byte arrays.
+//!
+//! `IntSentinel24`, `IntSentinel40`, `IntSentinel48` and `IntSentinel56` store an `Option<u64>` whose values fit in
+//! 24, 40, 48 and 56 bits respectively. They are backed by little-endian byte arrays, so that they have an alignment
+//! of 1 and take exactly 3, 5, 6 and 7 bytes, even in arrays. The all-ones pattern represents `None`.
+//!
+//! `PackedVec<T>` is a column of such values, that costs exactly `T::BYTES` bytes per row.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use sentinel_int::packed::IntSentinel48;
+//! let id = IntSentinel48::new_with_some(1 << 40);
+//! assert_eq!(id.to_option(), Some(1 << 40));
+//! assert_eq!(std::mem::size_of::<[IntSentinel48; 4]>(), 24);
+//! assert!(IntSentinel48::try_new_with_some(1 << 48).is_err());
+//! ```
+
+use core::cmp::Ordering;
+use core::convert::TryFrom;
+use core::fmt;
+#[cfg(feature = "std")]
+use core::iter::FromIterator;
+#[cfg(feature = "std")]
+use core::slice;
+#[cfg(feature = "std")]
+use std::error::Error;
+#[cfg(feature = "std")]
+use std::vec::Vec;
+
+use int_sentinel::IntSentinel;
+
+/// The error returned when a value does not fit in the width of a packed sentinel integer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct OutOfRangeError {
+    value: u64,
+    max: u64,
+}
+
+impl OutOfRangeError {
+    /// Returns the value that does not fit.
+    pub const fn value(&self) -> u64 {
+        self.value
+    }
+
+    /// Returns the maximum value of the packed sentinel integer.
+    pub const fn max(&self) -> u64 {
+        self.max
+    }
+}
+
+impl fmt::Display for OutOfRangeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Illegal value: {} is out of range, the maximum value is {}.", self.value, self.max)
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for OutOfRangeError {}
+
+mod private {
+    pub trait Sealed {}
+}
+
+/// The packed sentinel integers, i.e. the types that can be stored in a `PackedVec`.
+///
+/// This trait is sealed: the types implementing it are guaranteed to be transparent wrappers around `[u8; BYTES]`.
+pub trait PackedSentinel: private::Sealed + Copy {
+    /// The size of the type, in bytes.
+    const BYTES: usize;
+
+    /// Constructs an instance from an `Option`, or returns an error if the value does not fit.
+    fn try_from_option(option: Option<u64>) -> Result<Self, OutOfRangeError>;
+
+    /// Returns an `Option` corresponding to the value contained in this instance.
+    fn to_option(&self) -> Option<u64>;
+}
+
+macro_rules! packed_sentinel {
+    ($($name:ident => $bytes:expr;)*) => {
+        $(
+            #[doc = concat!("A compact representation for `Option<u64>` whose values fit in ", stringify!($bytes),
+                            " bytes, using the all-ones pattern as a sentinel.")]
+            ///
+            /// Instances are ordered like the corresponding `Option<u64>`, and the `Default` instance contains `None`.
+            ///
+            /// # Layout
+            ///
+            #[doc = concat!("This type is guaranteed to have the same layout as `[u8; ", stringify!($bytes),
+                            "]`, holding the raw value in little-endian order.")]
+            #[derive(Clone, Copy, PartialEq, Eq, Hash)]
+            #[repr(transparent)]
+            pub struct $name {
+                bytes: [u8; $bytes],
+            }
+
+            impl $name {
+                /// The number of bytes of the raw value.
+                pub const BYTES: usize = $bytes;
+
+                /// The sentinel value, i.e. the all-ones pattern.
+                pub const SENTINEL: u64 = u64::MAX >> (64 - 8 * $bytes);
+
+                /// The maximum value that can be represented by this type, see `max_value()`.
+                pub const MAX: u64 = Self::SENTINEL - 1;
+
+                /// An instance containing `None`, see `new_none()`.
+                pub const NONE: Self = $name { bytes: [0xff; $bytes] };
+
+                /// The maximum value that can be represented by this type.
+                pub const fn max_value() -> u64 {
+                    Self::MAX
+                }
+
+                /// Returns `true` if `value` can be stored as a `Some`, i.e. if it is lower than the sentinel.
+                pub const fn is_valid(value: u64) -> bool {
+                    value <= Self::MAX
+                }
+
+                #[doc = concat!("Constructs a new `", stringify!($name), "` containing `None`.")]
+                pub const fn new_none() -> Self {
+                    Self::NONE
+                }
+
+                #[doc = concat!("Constructs a new `", stringify!($name), "` containing the provided value.")]
+                ///
+                /// # Panics
+                ///
+                /// This function panics if `value` is not valid (i.e., if it is greater than `max_value()`).
+                /// When evaluated in a `const` context, the panic is reported as a compilation error.
+                pub const fn new_with_some(value: u64) -> Self {
+                    match $name::try_new_with_some(value) {
+                        Ok(sentinel) => sentinel,
+                        Err(_) => panic!("Illegal value: the value is out of range."),
+                    }
+                }
+
+                #[doc = concat!("Constructs a new `", stringify!($name), "` containing the provided value,")]
+                /// or returns an error if `value` is not valid (i.e., if it is greater than `max_value()`).
+                ///
+                /// # Examples
+                ///
+                /// ```rust
+                #[doc = concat!("# use sentinel_int::packed::", stringify!($name), ";")]
+                #[doc = concat!("assert!(", stringify!($name), "::try_new_with_some(", stringify!($name), "::MAX).is_ok());")]
+                #[doc = concat!("let error = ", stringify!($name), "::try_new_with_some(", stringify!($name), "::SENTINEL).unwrap_err();")]
+                #[doc = concat!("assert_eq!(error.max(), ", stringify!($name), "::MAX);")]
+                /// ```
+                pub const fn try_new_with_some(value: u64) -> Result<Self, OutOfRangeError> {
+                    if !Self::is_valid(value) {
+                        return Err(OutOfRangeError { value, max: Self::MAX });
+                    }
+                    Ok(unsafe { $name::unchecked_new(value) })
+                }
+
+                #[doc = concat!("Constructs a new `", stringify!($name), "` from an `Option`,")]
+                /// or returns an error if it contains a value that is not valid.
+                pub const fn try_from_option(option: Option<u64>) -> Result<Self, OutOfRangeError> {
+                    match option {
+                        Some(value) => $name::try_new_with_some(value),
+                        None => Ok($name::new_none()),
+                    }
+                }
+
+                /// Returns an `Option` corresponding to the value contained in this instance.
+                pub const fn to_option(&self) -> Option<u64> {
+                    let value = unsafe { self.to_u64_unchecked() };
+                    if value == Self::SENTINEL {
+                        None
+                    } else {
+                        Some(value)
+                    }
+                }
+
+                /// Returns `true` if this instance contains a value.
+                pub const fn is_some(&self) -> bool {
+                    self.to_option().is_some()
+                }
+
+                /// Returns `true` if this instance contains `None`.
+                pub const fn is_none(&self) -> bool {
+                    self.to_option().is_none()
+                }
+
+                /// Returns the corresponding `IntSentinel`.
+                pub const fn to_sentinel(&self) -> IntSentinel {
+                    match self.to_option() {
+                        Some(value) => IntSentinel::new_with_some(value),
+                        None => IntSentinel::NONE,
+                    }
+                }
+
+                #[doc = concat!("Constructs a new `", stringify!($name), "` from the low ", stringify!($bytes),
+                                " bytes of `value`, without a range check.")]
+                ///
+                /// # Safety
+                ///
+                /// The high bytes of `value` are discarded: if using this function with a value greater than
+                /// `max_value()`, the resulting instance contains the truncated value, or `None` if the truncated
+                /// value is the sentinel.
+                pub const unsafe fn unchecked_new(value: u64) -> Self {
+                    let le = value.to_le_bytes();
+                    let mut bytes = [0; $bytes];
+                    let mut i = 0;
+                    while i < $bytes {
+                        bytes[i] = le[i];
+                        i += 1;
+                    }
+                    $name { bytes }
+                }
+
+                /// Returns the raw contained value without a check.
+                ///
+                /// # Safety
+                ///
+                /// This method returns `SENTINEL` when the instance contains `None`, it returns the contained value
+                /// when the instance contains a different value.
+                pub const unsafe fn to_u64_unchecked(&self) -> u64 {
+                    let mut le = [0; 8];
+                    let mut i = 0;
+                    while i < $bytes {
+                        le[i] = self.bytes[i];
+                        i += 1;
+                    }
+                    u64::from_le_bytes(le)
+                }
+
+                /// Returns the raw value as little-endian bytes.
+                pub const fn to_le_bytes(self) -> [u8; $bytes] {
+                    self.bytes
+                }
+
+                /// Constructs a new instance from the little-endian bytes of the raw value.
+                pub const fn from_le_bytes(bytes: [u8; $bytes]) -> Self {
+                    $name { bytes }
+                }
+            }
+
+            impl private::Sealed for $name {}
+
+            impl PackedSentinel for $name {
+                const BYTES: usize = $bytes;
+
+                fn try_from_option(option: Option<u64>) -> Result<Self, OutOfRangeError> {
+                    $name::try_from_option(option)
+                }
+
+                fn to_option(&self) -> Option<u64> {
+                    $name::to_option(self)
+                }
+            }
+
+            impl Default for $name {
+                fn default() -> Self {
+                    $name::new_none()
+                }
+            }
+
+            impl fmt::Debug for $name {
+                fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                    f.debug_tuple(stringify!($name)).field(&self.to_option()).finish()
+                }
+            }
+
+            impl PartialOrd for $name {
+                fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                    Some(self.cmp(other))
+                }
+            }
+
+            impl Ord for $name {
+                fn cmp(&self, other: &Self) -> Ordering {
+                    self.to_option().cmp(&other.to_option())
+                }
+            }
+
+            impl From<Option<u64>> for $name {
+                /// # Panics
+                ///
+                /// This function panics if `option` contains a value greater than `max_value()`.
+                fn from(option: Option<u64>) -> Self {
+                    match $name::try_from_option(option) {
+                        Ok(sentinel) => sentinel,
+                        Err(error) => panic!("{}", error),
+                    }
+                }
+            }
+
+            impl TryFrom<u64> for $name {
+                type Error = OutOfRangeError;
+
+                fn try_from(value: u64) -> Result<Self, Self::Error> {
+                    $name::try_new_with_some(value)
+                }
+            }
+
+            impl TryFrom<IntSentinel> for $name {
+                type Error = OutOfRangeError;
+
+                fn try_from(sentinel: IntSentinel) -> Result<Self, Self::Error> {
+                    $name::try_from_option(sentinel.to_option())
+                }
+            }
+
+            impl From<$name> for Option<u64> {
+                fn from(sentinel: $name) -> Self {
+                    sentinel.to_option()
+                }
+            }
+
+            impl From<$name> for IntSentinel {
+                fn from(sentinel: $name) -> Self {
+                    sentinel.to_sentinel()
+                }
+            }
+        )*
+    };
+}
+
+packed_sentinel! {
+    IntSentinel24 => 3;
+    IntSentinel40 => 5;
+    IntSentinel48 => 6;
+    IntSentinel56 => 7;
+}
+
+/// A column of packed sentinel integers, costing exactly `T::BYTES` bytes per row.
+///
+/// # Examples
+///
+/// ```rust
+/// use sentinel_int::packed::{IntSentinel48, PackedVec};
+/// let mut ids: PackedVec<IntSentinel48> = PackedVec::new();
+/// ids.push(Some(42));
+/// ids.push(None);
+/// assert_eq!(ids.as_bytes().len(), 12);
+/// ```
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct PackedVec<T> {
+    values: Vec<T>,
+}
+
+#[cfg(feature = "std")]
+impl<T: PackedSentinel> PackedVec<T> {
+    /// Constructs a new, empty `PackedVec`.
+    pub fn new() -> Self {
+        PackedVec { values: Vec::new() }
+    }
+
+    /// Constructs a new, empty `PackedVec` with room for `capacity` rows without reallocating.
+    pub fn with_capacity(capacity: usize) -> Self {
+        PackedVec { values: Vec::with_capacity(capacity) }
+    }
+
+    /// Returns the number of rows, including the `None` rows.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Returns `true` if there are no rows.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Appends a row.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `value` contains a value that does not fit in `T`.
+    pub fn push(&mut self, value: Option<u64>) {
+        if let Err(error) = self.try_push(value) {
+            panic!("{}", error);
+        }
+    }
+
+    /// Appends a row, or returns an error if `value` contains a value that does not fit in `T`.
+    pub fn try_push(&mut self, value: Option<u64>) -> Result<(), OutOfRangeError> {
+        self.values.push(T::try_from_option(value)?);
+        Ok(())
+    }
+
+    /// Returns the value of the row at `index`.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `index` is out of bounds, as `None` is a legitimate row value.
+    pub fn get(&self, index: usize) -> Option<u64> {
+        self.values[index].to_option()
+    }
+
+    /// Replaces the value of the row at `index`, returning the previous value.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `index` is out of bounds, or if `value` contains a value that does not fit in `T`.
+    pub fn set(&mut self, index: usize, value: Option<u64>) -> Option<u64> {
+        let value = match T::try_from_option(value) {
+            Ok(value) => value,
+            Err(error) => panic!("{}", error),
+        };
+        let previous = self.values[index].to_option();
+        self.values[index] = value;
+        previous
+    }
+
+    /// Returns an iterator over the values of all rows.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = Option<u64>> + ExactSizeIterator + '_ {
+        self.values.iter().map(T::to_option)
+    }
+
+    /// Returns the rows as a slice of `T`.
+    pub fn as_slice(&self) -> &[T] {
+        &self.values
+    }
+
+    /// Returns the raw bytes of the rows, `T::BYTES` little-endian bytes per row.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use sentinel_int::packed::{IntSentinel24, PackedVec};
+    /// let column: PackedVec<IntSentinel24> = vec![Some(1), None].into_iter().collect();
+    /// assert_eq!(column.as_bytes(), &[1, 0, 0, 0xff, 0xff, 0xff]);
+    /// ```
+    pub fn as_bytes(&self) -> &[u8] {
+        // `T` is a transparent wrapper around `[u8; T::BYTES]`, as guaranteed by the sealed `PackedSentinel` trait.
+        unsafe { slice::from_raw_parts(self.values.as_ptr() as *const u8, self.values.len() * T::BYTES) }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: PackedSentinel> FromIterator<Option<u64>> for PackedVec<T> {
+    /// # Panics
+    ///
+    /// This function panics if any value does not fit in `T`.
+    fn from_iter<I: IntoIterator<Item = Option<u64>>>(iter: I) -> Self {
+        let mut vec = PackedVec::new();
+        vec.extend(iter);
+        vec
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: PackedSentinel> Extend<Option<u64>> for PackedVec<T> {
+    /// # Panics
+    ///
+    /// This function panics if any value does not fit in `T`.
+    fn extend<I: IntoIterator<Item = Option<u64>>>(&mut self, iter: I) {
+        for value in iter {
+            self.push(value);
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T> From<Vec<T>> for PackedVec<T> {
+    fn from(values: Vec<T>) -> Self {
+        PackedVec { values }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T> From<PackedVec<T>> for Vec<T> {
+    fn from(vec: PackedVec<T>) -> Self {
+        vec.values
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    macro_rules! packed_sentinel_tests {
+        ($($module:ident => $name:ident, $bytes:expr;)*) => {
+            $(
+                mod $module {
+                    use core::convert::TryFrom;
+                    use core::mem::{align_of, size_of};
+
+                    use int_sentinel::IntSentinel;
+                    use packed::$name;
+
+                    #[test]
+                    fn layout() {
+                        assert_eq!(size_of::<$name>(), $bytes);
+                        assert_eq!(align_of::<$name>(), 1);
+                        assert_eq!(size_of::<[$name; 3]>(), 3 * $bytes);
+                    }
+
+                    #[test]
+                    fn round_trip() {
+                        for &option in [None, Some(0), Some(42), Some($name::MAX)].iter() {
+                            let sentinel = $name::from(option);
+                            assert_eq!(sentinel.to_option(), option);
+                            assert_eq!(Option::<u64>::from(sentinel), option);
+                            assert_eq!(IntSentinel::from(sentinel).to_option(), option);
+                            assert_eq!($name::try_from(IntSentinel::from(option)), Ok(sentinel));
+                            assert_eq!($name::from_le_bytes(sentinel.to_le_bytes()), sentinel);
+                        }
+                        assert_eq!($name::MAX, (1 << (8 * $bytes)) - 2);
+                    }
+
+                    #[test]
+                    fn range_check() {
+                        assert!($name::try_from($name::SENTINEL).is_err());
+                        assert!($name::try_from(u64::MAX - 1).is_err());
+                        assert!($name::try_from(IntSentinel::from(Some($name::SENTINEL))).is_err());
+                        assert_eq!($name::try_from_option(Some($name::MAX + 1)).unwrap_err().value(), $name::MAX + 1);
+                        assert_eq!(unsafe { $name::unchecked_new($name::SENTINEL) }, $name::NONE);
+                    }
+
+                    #[test]
+                    fn ordering_matches_option() {
+                        let values = [None, Some(0), Some(1), Some($name::MAX)];
+                        for &a in values.iter() {
+                            for &b in values.iter() {
+                                assert_eq!($name::from(a).cmp(&$name::from(b)), a.cmp(&b));
+                            }
+                        }
+                    }
+                }
+            )*
+        };
+    }
+
+    packed_sentinel_tests! {
+        sentinel24 => IntSentinel24, 3;
+        sentinel40 => IntSentinel40, 5;
+        sentinel48 => IntSentinel48, 6;
+        sentinel56 => IntSentinel56, 7;
+    }
+
+    #[test]
+    fn const_construction() {
+        const ID: IntSentinel48 = IntSentinel48::new_with_some(1 << 47);
+        assert_eq!(ID.to_option(), Some(1 << 47));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn packed_vec() {
+        use core::mem::size_of;
+
+        let mut column: PackedVec<IntSentinel40> = PackedVec::with_capacity(3);
+        column.push(Some(1 << 32));
+        column.push(None);
+        assert!(column.try_push(Some(1 << 40)).is_err());
+        assert_eq!(column.len(), 2);
+        assert_eq!(column.set(1, Some(7)), None);
+        assert_eq!(column.get(0), Some(1 << 32));
+        assert_eq!(column.iter().collect::<Vec<_>>(), vec![Some(1 << 32), Some(7)]);
+        assert_eq!(column.as_bytes(), &[0, 0, 0, 0, 1, 7, 0, 0, 0, 0]);
+        assert_eq!(size_of::<IntSentinel40>() * column.len(), column.as_bytes().len());
+    }
+}
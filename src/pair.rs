@@ -0,0 +1,228 @@
+//! Two optional `u32` packed into a single `u64`.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use sentinel_int::pair::SentinelPair32;
+//! let mut edge = SentinelPair32::new(Some(1), None);
+//! assert_eq!(edge.first(), Some(1));
+//! assert_eq!(edge.second(), None);
+//! edge.set_second(Some(2));
+//! assert_eq!(edge.to_tuple(), (Some(1), Some(2)));
+//! assert_eq!(std::mem::size_of::<SentinelPair32>(), 8);
+//! ```
+
+use core::cmp::Ordering;
+
+use error::SentinelError;
+use int_sentinel::IntSentinelU32;
+
+/// A compact representation for `(Option<u32>, Option<u32>)`, storing each half as an `IntSentinelU32`.
+///
+/// The first half is stored in the high 32 bits of the `u64`, the second half in the low 32 bits, and each half
+/// uses `u32::MAX` as its sentinel.
+///
+/// Instances are ordered like the corresponding tuple of options, i.e. lexicographically with `None` first.
+/// The `Default` instance contains `(None, None)`.
+///
+/// # Layout
+///
+/// This type is guaranteed to have the same layout as `u64`, and every `u64` bit pattern is a valid instance.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct SentinelPair32 {
+    value: u64,
+}
+
+impl SentinelPair32 {
+    /// An instance containing `(None, None)`.
+    pub const NONE: Self = SentinelPair32 { value: u64::MAX };
+
+    /// Constructs a new `SentinelPair32` containing the provided halves.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if one of the halves is `Some(u32::MAX)`.
+    pub const fn new(first: Option<u32>, second: Option<u32>) -> Self {
+        match SentinelPair32::try_new(first, second) {
+            Ok(pair) => pair,
+            Err(_) => panic!("Illegal value: the sentinel value cannot be stored as a `Some`."),
+        }
+    }
+
+    /// Constructs a new `SentinelPair32` containing the provided halves,
+    /// or returns an error if one of them is `Some(u32::MAX)`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use sentinel_int::pair::SentinelPair32;
+    /// assert!(SentinelPair32::try_new(Some(1), None).is_ok());
+    /// assert_eq!(SentinelPair32::try_new(None, Some(u32::MAX)).unwrap_err().value(), u32::MAX);
+    /// ```
+    pub const fn try_new(first: Option<u32>, second: Option<u32>) -> Result<Self, SentinelError<u32>> {
+        let first = match IntSentinelU32::try_from_option(first) {
+            Ok(first) => first,
+            Err(error) => return Err(error),
+        };
+        let second = match IntSentinelU32::try_from_option(second) {
+            Ok(second) => second,
+            Err(error) => return Err(error),
+        };
+        Ok(SentinelPair32::from_halves(first, second))
+    }
+
+    /// Constructs a new `SentinelPair32` from its two halves.
+    pub const fn from_halves(first: IntSentinelU32, second: IntSentinelU32) -> Self {
+        let (first, second) = unsafe { (first.to_u32_unchecked(), second.to_u32_unchecked()) };
+        SentinelPair32 { value: (first as u64) << 32 | second as u64 }
+    }
+
+    /// Returns the first half as an `IntSentinelU32`.
+    pub const fn first_half(&self) -> IntSentinelU32 {
+        unsafe { IntSentinelU32::unchecked_new((self.value >> 32) as u32) }
+    }
+
+    /// Returns the second half as an `IntSentinelU32`.
+    pub const fn second_half(&self) -> IntSentinelU32 {
+        unsafe { IntSentinelU32::unchecked_new(self.value as u32) }
+    }
+
+    /// Returns the first half.
+    pub const fn first(&self) -> Option<u32> {
+        self.first_half().to_option()
+    }
+
+    /// Returns the second half.
+    pub const fn second(&self) -> Option<u32> {
+        self.second_half().to_option()
+    }
+
+    /// Replaces the first half.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `first` is `Some(u32::MAX)`.
+    pub fn set_first(&mut self, first: Option<u32>) {
+        *self = SentinelPair32::from_halves(IntSentinelU32::from(first), self.second_half());
+    }
+
+    /// Replaces the second half.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `second` is `Some(u32::MAX)`.
+    pub fn set_second(&mut self, second: Option<u32>) {
+        *self = SentinelPair32::from_halves(self.first_half(), IntSentinelU32::from(second));
+    }
+
+    /// Returns the two halves as a tuple of options.
+    pub const fn to_tuple(&self) -> (Option<u32>, Option<u32>) {
+        (self.first(), self.second())
+    }
+
+    /// Returns the raw `u64`, with the first half in the high 32 bits.
+    pub const fn to_u64(self) -> u64 {
+        self.value
+    }
+
+    /// Constructs a new `SentinelPair32` from a raw `u64`, with the first half in the high 32 bits.
+    ///
+    /// Every `u64` is a valid pair: a half equal to `u32::MAX` is read as `None`, any other half as a `Some`.
+    pub const fn from_u64(value: u64) -> Self {
+        SentinelPair32 { value }
+    }
+}
+
+impl Default for SentinelPair32 {
+    fn default() -> Self {
+        SentinelPair32::NONE
+    }
+}
+
+impl PartialOrd for SentinelPair32 {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for SentinelPair32 {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.to_tuple().cmp(&other.to_tuple())
+    }
+}
+
+impl From<(Option<u32>, Option<u32>)> for SentinelPair32 {
+    /// # Panics
+    ///
+    /// This function panics if one of the halves is `Some(u32::MAX)`.
+    fn from((first, second): (Option<u32>, Option<u32>)) -> Self {
+        SentinelPair32::new(first, second)
+    }
+}
+
+impl From<SentinelPair32> for (Option<u32>, Option<u32>) {
+    fn from(pair: SentinelPair32) -> Self {
+        pair.to_tuple()
+    }
+}
+
+impl From<(IntSentinelU32, IntSentinelU32)> for SentinelPair32 {
+    fn from((first, second): (IntSentinelU32, IntSentinelU32)) -> Self {
+        SentinelPair32::from_halves(first, second)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OPTIONS: [Option<u32>; 4] = [None, Some(0), Some(7), Some(u32::MAX - 1)];
+
+    #[test]
+    fn halves() {
+        for &first in OPTIONS.iter() {
+            for &second in OPTIONS.iter() {
+                let pair = SentinelPair32::from((first, second));
+                assert_eq!(pair.first(), first);
+                assert_eq!(pair.second(), second);
+                assert_eq!(<(Option<u32>, Option<u32>)>::from(pair), (first, second));
+                assert_eq!(SentinelPair32::from_u64(pair.to_u64()), pair);
+            }
+        }
+        assert_eq!(SentinelPair32::new(Some(1), Some(2)).to_u64(), 1 << 32 | 2);
+        assert_eq!(SentinelPair32::default().to_tuple(), (None, None));
+    }
+
+    #[test]
+    fn setters() {
+        let mut pair = SentinelPair32::NONE;
+        pair.set_first(Some(3));
+        assert_eq!(pair.to_tuple(), (Some(3), None));
+        pair.set_second(Some(4));
+        pair.set_first(None);
+        assert_eq!(pair.to_tuple(), (None, Some(4)));
+        assert_eq!(SentinelPair32::from((IntSentinelU32::NONE, IntSentinelU32::new_with_some(4))), pair);
+    }
+
+    #[test]
+    fn ordering_matches_tuple() {
+        for &a in OPTIONS.iter() {
+            for &b in OPTIONS.iter() {
+                for &c in OPTIONS.iter() {
+                    for &d in OPTIONS.iter() {
+                        let (x, y) = (SentinelPair32::new(a, b), SentinelPair32::new(c, d));
+                        assert_eq!(x.cmp(&y), (a, b).cmp(&(c, d)));
+                    }
+                }
+            }
+        }
+    }
+
+    #[should_panic]
+    #[test]
+    fn illegal_value() {
+        let mut pair = SentinelPair32::default();
+        pair.set_second(Some(u32::MAX));
+    }
+}
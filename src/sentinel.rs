@@ -0,0 +1,364 @@
+use core::fmt;
+use core::hash::{Hash, Hasher};
+
+use error::SentinelError;
+
+/// A type whose values can be stored in a raw representation that reserves a sentinel value for `None`.
+///
+/// Implementing this trait for a type makes `Sentinelled<T>` available as a compact representation of `Option<T>`.
+///
+/// # Examples
+///
+/// ```rust
+/// # use sentinel_int::sentinel::{Sentinel, Sentinelled};
+/// #[derive(Debug, PartialEq)]
+/// struct RowId(u32);
+///
+/// impl Sentinel for RowId {
+///     type Raw = u32;
+///
+///     fn sentinel() -> u32 {
+///         u32::MAX
+///     }
+///
+///     fn into_raw(self) -> u32 {
+///         self.0
+///     }
+///
+///     fn from_raw(raw: u32) -> Self {
+///         RowId(raw)
+///     }
+/// }
+///
+/// let sentinel = Sentinelled::from(Some(RowId(42)));
+/// assert_eq!(sentinel.to_option(), Some(RowId(42)));
+/// ```
+pub trait Sentinel: Sized {
+    /// The raw representation of the values of this type.
+    type Raw: Copy + PartialEq + fmt::Debug;
+
+    /// The raw value that represents `None`.
+    fn sentinel() -> Self::Raw;
+
+    /// Returns `true` if `raw` is the sentinel value.
+    fn is_sentinel(raw: &Self::Raw) -> bool {
+        *raw == Self::sentinel()
+    }
+
+    /// Converts a value into its raw representation.
+    ///
+    /// The returned raw value may be the sentinel, in which case the value cannot be represented as a `Some`.
+    fn into_raw(self) -> Self::Raw;
+
+    /// Converts a raw representation back into a value.
+    ///
+    /// This function is never called with the sentinel value by `Sentinelled`.
+    fn from_raw(raw: Self::Raw) -> Self;
+}
+
+macro_rules! int_sentinel_impl {
+    ($($t:ident => $sentinel:ident),*) => {
+        $(
+            impl Sentinel for $t {
+                type Raw = $t;
+
+                fn sentinel() -> $t {
+                    $t::$sentinel
+                }
+
+                fn into_raw(self) -> $t {
+                    self
+                }
+
+                fn from_raw(raw: $t) -> Self {
+                    raw
+                }
+            }
+        )*
+    };
+}
+
+int_sentinel_impl!(u8 => MAX, u16 => MAX, u32 => MAX, u64 => MAX, u128 => MAX, usize => MAX);
+int_sentinel_impl!(i8 => MIN, i16 => MIN, i32 => MIN, i64 => MIN, i128 => MIN, isize => MIN);
+
+/// A compact representation for `Option<T>`, obtained by storing the raw representation of `T`
+/// and using `T::sentinel()` to represent `None`.
+///
+/// This is the generic counterpart of the `IntSentinel` family, for any type implementing `Sentinel`.
+/// Unlike the `IntSentinel` family, it has no `max_value()`: the values that can be stored are the ones whose raw
+/// representation is not a sentinel according to `Sentinel::is_sentinel`, which need not form a range.
+/// Use `is_valid()` to check a specific raw value instead.
+///
+/// Two instances are equal if they both contain `None`, or if their raw representations are equal.
+///
+/// # Examples
+///
+/// ```rust
+/// # use sentinel_int::sentinel::Sentinelled;
+/// let sentinel = Sentinelled::from(Some(42u64));
+/// assert_eq!(Option::<u64>::from(sentinel), Some(42u64));
+///
+/// let sentinel = Sentinelled::<i64>::from(None);
+/// assert_eq!(Option::<i64>::from(sentinel), None);
+/// ```
+pub struct Sentinelled<T: Sentinel> {
+    raw: T::Raw,
+}
+
+impl<T: Sentinel> Sentinelled<T> {
+    /// The sentinel value.
+    pub fn sentinel() -> T::Raw {
+        T::sentinel()
+    }
+
+    /// Returns `true` if `raw` can be stored as a `Some`, i.e. if it is not a sentinel value.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use sentinel_int::sentinel::Sentinelled;
+    /// assert!(Sentinelled::<u8>::is_valid(&42));
+    /// assert!(!Sentinelled::<u8>::is_valid(&u8::MAX));
+    /// ```
+    pub fn is_valid(raw: &T::Raw) -> bool {
+        !T::is_sentinel(raw)
+    }
+
+    /// Constructs a new `Sentinelled` containing `None`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use sentinel_int::sentinel::Sentinelled;
+    /// let sentinel = Sentinelled::<u32>::new_none();
+    /// assert_eq!(sentinel.to_option(), None);
+    /// ```
+    pub fn new_none() -> Self {
+        Sentinelled { raw: T::sentinel() }
+    }
+
+    /// Constructs a new `Sentinelled` containing the provided value.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the raw representation of `value` is the sentinel value.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use sentinel_int::sentinel::Sentinelled;
+    /// let sentinel = Sentinelled::new_with_some(42u32);
+    /// assert_eq!(sentinel.to_option(), Some(42u32));
+    /// ```
+    pub fn new_with_some(value: T) -> Self {
+        match Sentinelled::try_new_with_some(value) {
+            Ok(sentinel) => sentinel,
+            Err(error) => panic!("{}", error),
+        }
+    }
+
+    /// Constructs a new `Sentinelled` containing the provided value,
+    /// or returns an error carrying the raw representation of `value` if it is the sentinel value.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use sentinel_int::sentinel::Sentinelled;
+    /// assert!(Sentinelled::try_new_with_some(42u32).is_ok());
+    /// assert_eq!(Sentinelled::try_new_with_some(u32::MAX).unwrap_err().value(), u32::MAX);
+    /// ```
+    pub fn try_new_with_some(value: T) -> Result<Self, SentinelError<T::Raw>> {
+        let raw = value.into_raw();
+        if T::is_sentinel(&raw) {
+            return Err(SentinelError::new(raw));
+        }
+        Ok(Sentinelled { raw })
+    }
+
+    /// Returns an `Option` corresponding to the value contained in this instance.
+    pub fn to_option(&self) -> Option<T> {
+        if T::is_sentinel(&self.raw) {
+            None
+        } else {
+            Some(T::from_raw(self.raw))
+        }
+    }
+
+    /// Constructs a new `Sentinelled` from a raw value without checking the sentinel value.
+    ///
+    /// # Safety
+    ///
+    /// If using this function to create a `Sentinelled`, `sentinel()` will be transformed into a `None` value,
+    /// and any other raw value will be mapped to a `Some` of the corresponding value.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use sentinel_int::sentinel::Sentinelled;
+    /// unsafe {
+    ///     assert_eq!(Sentinelled::<u8>::unchecked_new(u8::MAX).to_option(), None)
+    /// }
+    /// ```
+    pub unsafe fn unchecked_new(raw: T::Raw) -> Self {
+        Sentinelled { raw }
+    }
+
+    /// Returns the raw contained value without a check.
+    ///
+    /// # Safety
+    ///
+    /// This method returns `sentinel()` when the instance contains `None`, it returns the raw representation of the
+    /// contained value when the instance contains a different value.
+    pub unsafe fn to_raw_unchecked(&self) -> T::Raw {
+        self.raw
+    }
+}
+
+impl<T: Sentinel> Clone for Sentinelled<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: Sentinel> Copy for Sentinelled<T> {}
+
+impl<T: Sentinel> PartialEq for Sentinelled<T> {
+    fn eq(&self, other: &Self) -> bool {
+        match (T::is_sentinel(&self.raw), T::is_sentinel(&other.raw)) {
+            (true, true) => true,
+            (false, false) => self.raw == other.raw,
+            _ => false,
+        }
+    }
+}
+
+impl<T: Sentinel> Eq for Sentinelled<T> where T::Raw: Eq {}
+
+impl<T: Sentinel> Hash for Sentinelled<T> where T::Raw: Hash {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        if T::is_sentinel(&self.raw) {
+            T::sentinel().hash(state)
+        } else {
+            self.raw.hash(state)
+        }
+    }
+}
+
+impl<T: Sentinel> fmt::Debug for Sentinelled<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Sentinelled").field("raw", &self.raw).finish()
+    }
+}
+
+impl<T: Sentinel> From<Option<T>> for Sentinelled<T> {
+    fn from(option: Option<T>) -> Self {
+        match option {
+            Some(value) => Sentinelled::new_with_some(value),
+            None => Sentinelled::new_none()
+        }
+    }
+}
+
+impl<T: Sentinel> From<Sentinelled<T>> for Option<T> {
+    fn from(sentinel: Sentinelled<T>) -> Self {
+        sentinel.to_option()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A fixed-point price with 4 decimal digits, where negative prices are not representable.
+    #[derive(Debug, PartialEq)]
+    struct Price(i64);
+
+    impl Sentinel for Price {
+        type Raw = i64;
+
+        fn sentinel() -> i64 {
+            -1
+        }
+
+        fn is_sentinel(raw: &i64) -> bool {
+            *raw < 0
+        }
+
+        fn into_raw(self) -> i64 {
+            self.0
+        }
+
+        fn from_raw(raw: i64) -> Self {
+            Price(raw)
+        }
+    }
+
+    #[test]
+    fn some_value() {
+        let sentinel = Sentinelled::new_with_some(Price(12_5000));
+        assert_eq!(sentinel.to_option(), Some(Price(12_5000)));
+    }
+
+    #[test]
+    fn none_value() {
+        let sentinel = Sentinelled::<Price>::new_none();
+        assert_eq!(sentinel.to_option(), None);
+        assert_eq!(unsafe { sentinel.to_raw_unchecked() }, -1);
+    }
+
+    #[test]
+    fn using_from() {
+        assert_eq!(Option::<Price>::from(Sentinelled::from(Some(Price(0)))), Some(Price(0)));
+        assert_eq!(Option::<Price>::from(Sentinelled::<Price>::from(None)), None);
+    }
+
+    #[test]
+    fn primitive_sentinels() {
+        assert_eq!(Sentinelled::<u16>::sentinel(), u16::MAX);
+        assert_eq!(Sentinelled::<i16>::sentinel(), i16::MIN);
+        assert_eq!(Sentinelled::from(Some(-42i16)).to_option(), Some(-42i16));
+    }
+
+    #[test]
+    fn unchecked_sentinel() {
+        let sentinel = unsafe { Sentinelled::<Price>::unchecked_new(-7) };
+        assert_eq!(sentinel.to_option(), None);
+    }
+
+    #[test]
+    fn try_illegal_value() {
+        let error = Sentinelled::try_new_with_some(Price(-3)).unwrap_err();
+        assert_eq!(error.value(), -3);
+    }
+
+    #[test]
+    fn value_traits() {
+        let none = unsafe { Sentinelled::<Price>::unchecked_new(-7) };
+        let copy = none;
+        assert_eq!(copy, Sentinelled::new_none());
+        assert_ne!(Sentinelled::new_with_some(Price(1)), none);
+        assert_ne!(Sentinelled::new_with_some(Price(1)), Sentinelled::new_with_some(Price(2)));
+        assert!(Sentinelled::<Price>::is_valid(&0));
+        assert!(!Sentinelled::<Price>::is_valid(&-7));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn hash_set() {
+        use std::collections::HashSet;
+        let set: HashSet<_> = [Some(1), None, Some(1), None].iter().map(|&x| Sentinelled::<u64>::from(x)).collect();
+        assert_eq!(set.len(), 2);
+        assert!(set.contains(&Sentinelled::new_none()));
+    }
+
+    #[should_panic]
+    #[test]
+    fn some_illegal_value() {
+        Sentinelled::new_with_some(Price(-3));
+    }
+
+    #[should_panic]
+    #[test]
+    fn using_from_illegal_value() {
+        let _ = Sentinelled::from(Some(u64::MAX));
+    }
+}
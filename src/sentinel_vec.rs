@@ -0,0 +1,266 @@
+use core::iter::{Enumerate, FromIterator};
+use core::slice;
+use std::vec::Vec;
+
+use int_sentinel::IntSentinel;
+
+/// A dense column of `Option<u64>`, stored as `IntSentinel` so that each row costs exactly 8 bytes.
+///
+/// # Examples
+///
+/// ```rust
+/// # use sentinel_int::sentinel_vec::SentinelVec;
+/// let mut column = SentinelVec::new();
+/// column.push(Some(42));
+/// column.push(None);
+/// column.push(Some(7));
+/// assert_eq!(column.get(0), Some(42));
+/// assert_eq!(column.get(1), None);
+/// assert_eq!(column.count_some(), 2);
+/// assert_eq!(column.iter_some().collect::<Vec<_>>(), vec![(0, 42), (2, 7)]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct SentinelVec {
+    values: Vec<IntSentinel>,
+}
+
+impl SentinelVec {
+    /// Constructs a new, empty `SentinelVec`.
+    pub fn new() -> Self {
+        SentinelVec { values: Vec::new() }
+    }
+
+    /// Constructs a new, empty `SentinelVec` with room for `capacity` rows without reallocating.
+    pub fn with_capacity(capacity: usize) -> Self {
+        SentinelVec { values: Vec::with_capacity(capacity) }
+    }
+
+    /// Returns the number of rows, including the `None` rows.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Returns `true` if there are no rows.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Appends a row.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `value` is `Some(IntSentinel::sentinel())`.
+    pub fn push(&mut self, value: Option<u64>) {
+        self.values.push(IntSentinel::from(value));
+    }
+
+    /// Returns the value of the row at `index`.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `index` is out of bounds, as `None` is a legitimate row value.
+    pub fn get(&self, index: usize) -> Option<u64> {
+        self.values[index].to_option()
+    }
+
+    /// Replaces the value of the row at `index`, returning the previous value.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `index` is out of bounds, or if `value` is `Some(IntSentinel::sentinel())`.
+    pub fn set(&mut self, index: usize, value: Option<u64>) -> Option<u64> {
+        let previous = self.values[index];
+        self.values[index] = IntSentinel::from(value);
+        previous.to_option()
+    }
+
+    /// Takes the value of the row at `index`, leaving `None` in its place.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `index` is out of bounds.
+    pub fn take(&mut self, index: usize) -> Option<u64> {
+        self.values[index].take().to_option()
+    }
+
+    /// Returns the number of rows that contain a value.
+    pub fn count_some(&self) -> usize {
+        self.values.iter().filter(|value| value.is_some()).count()
+    }
+
+    /// Returns an iterator over the values of all rows.
+    pub fn iter(&self) -> Iter<'_> {
+        Iter { values: self.values.iter() }
+    }
+
+    /// Returns an iterator over the rows that contain a value, yielding their index along with their value.
+    pub fn iter_some(&self) -> IterSome<'_> {
+        IterSome { values: self.values.iter().enumerate() }
+    }
+
+    /// Removes all the `None` rows, keeping the order of the other rows.
+    pub fn retain_some(&mut self) {
+        self.values.retain(|value| value.is_some());
+    }
+
+    /// Returns the rows as a slice of `IntSentinel`.
+    pub fn as_slice(&self) -> &[IntSentinel] {
+        &self.values
+    }
+
+    /// Returns the rows as a mutable slice of `IntSentinel`.
+    pub fn as_mut_slice(&mut self) -> &mut [IntSentinel] {
+        &mut self.values
+    }
+}
+
+impl From<Vec<IntSentinel>> for SentinelVec {
+    fn from(values: Vec<IntSentinel>) -> Self {
+        SentinelVec { values }
+    }
+}
+
+impl From<SentinelVec> for Vec<IntSentinel> {
+    fn from(vec: SentinelVec) -> Self {
+        vec.values
+    }
+}
+
+impl From<Vec<Option<u64>>> for SentinelVec {
+    /// # Panics
+    ///
+    /// This function panics if any value is `Some(IntSentinel::sentinel())`.
+    fn from(values: Vec<Option<u64>>) -> Self {
+        values.into_iter().collect()
+    }
+}
+
+impl From<SentinelVec> for Vec<Option<u64>> {
+    fn from(vec: SentinelVec) -> Self {
+        vec.iter().collect()
+    }
+}
+
+impl FromIterator<Option<u64>> for SentinelVec {
+    fn from_iter<I: IntoIterator<Item = Option<u64>>>(iter: I) -> Self {
+        SentinelVec { values: iter.into_iter().map(IntSentinel::from).collect() }
+    }
+}
+
+impl Extend<Option<u64>> for SentinelVec {
+    fn extend<I: IntoIterator<Item = Option<u64>>>(&mut self, iter: I) {
+        self.values.extend(iter.into_iter().map(IntSentinel::from))
+    }
+}
+
+impl<'a> IntoIterator for &'a SentinelVec {
+    type Item = Option<u64>;
+    type IntoIter = Iter<'a>;
+
+    fn into_iter(self) -> Iter<'a> {
+        self.iter()
+    }
+}
+
+/// An iterator over the values of the rows of a `SentinelVec`, see `SentinelVec::iter`.
+#[derive(Debug, Clone)]
+pub struct Iter<'a> {
+    values: slice::Iter<'a, IntSentinel>,
+}
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = Option<u64>;
+
+    fn next(&mut self) -> Option<Option<u64>> {
+        self.values.next().map(IntSentinel::to_option)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.values.size_hint()
+    }
+}
+
+impl<'a> DoubleEndedIterator for Iter<'a> {
+    fn next_back(&mut self) -> Option<Option<u64>> {
+        self.values.next_back().map(IntSentinel::to_option)
+    }
+}
+
+impl<'a> ExactSizeIterator for Iter<'a> {}
+
+/// An iterator over the rows of a `SentinelVec` that contain a value, see `SentinelVec::iter_some`.
+#[derive(Debug, Clone)]
+pub struct IterSome<'a> {
+    values: Enumerate<slice::Iter<'a, IntSentinel>>,
+}
+
+impl<'a> Iterator for IterSome<'a> {
+    type Item = (usize, u64);
+
+    fn next(&mut self) -> Option<(usize, u64)> {
+        self.values.by_ref().filter_map(|(index, value)| value.to_option().map(|value| (index, value))).next()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::vec::Vec;
+
+    use super::*;
+
+    #[test]
+    fn push_and_get() {
+        let mut column = SentinelVec::with_capacity(3);
+        assert!(column.is_empty());
+        column.push(Some(1));
+        column.push(None);
+        column.push(Some(u64::MAX - 1));
+        assert_eq!(column.len(), 3);
+        assert_eq!(column.get(0), Some(1));
+        assert_eq!(column.get(1), None);
+        assert_eq!(column.get(2), Some(u64::MAX - 1));
+    }
+
+    #[test]
+    fn set_and_take() {
+        let mut column = SentinelVec::from(vec![Some(1), None]);
+        assert_eq!(column.set(1, Some(2)), None);
+        assert_eq!(column.set(0, None), Some(1));
+        assert_eq!(column.take(1), Some(2));
+        assert_eq!(Vec::<Option<u64>>::from(column), vec![None, None]);
+    }
+
+    #[test]
+    fn iterators() {
+        let column: SentinelVec = vec![None, Some(3), None, Some(5)].into();
+        assert_eq!(column.count_some(), 2);
+        assert_eq!(column.iter().collect::<Vec<_>>(), vec![None, Some(3), None, Some(5)]);
+        assert_eq!(column.iter().next_back(), Some(Some(5)));
+        assert_eq!(column.iter_some().collect::<Vec<_>>(), vec![(1, 3), (3, 5)]);
+    }
+
+    #[test]
+    fn retain_some() {
+        let mut column: SentinelVec = vec![None, Some(3), None, Some(5)].into();
+        column.retain_some();
+        assert_eq!(Vec::<Option<u64>>::from(column), vec![Some(3), Some(5)]);
+    }
+
+    #[test]
+    fn size_per_row() {
+        let column: SentinelVec = vec![None; 16].into();
+        assert_eq!(::std::mem::size_of_val(column.as_slice()), 16 * 8);
+    }
+
+    #[should_panic]
+    #[test]
+    fn push_illegal_value() {
+        SentinelVec::new().push(Some(u64::MAX));
+    }
+
+    #[should_panic]
+    #[test]
+    fn get_out_of_bounds() {
+        SentinelVec::new().get(0);
+    }
+}
@@ -0,0 +1,506 @@
+//! A slab allocator whose free list is threaded through the vacant slots.
+//!
+//! Each vacant slot stores the key of the next vacant slot as an `IntSentinel`, in place of a value, with `None`
+//! marking the end of the list: the free list needs no allocation of its own. Whether a slot is occupied is tracked in
+//! a separate bitset rather than with a discriminant, so that a slot is as large as the larger of `T` and `u64`, and
+//! a vacant slot never costs more than one `u64` beyond the space of a value.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use sentinel_int::slab::SentinelSlab;
+//! let mut slab = SentinelSlab::new();
+//! let hello = slab.insert("hello");
+//! let world = slab.insert("world");
+//! assert_eq!(slab[hello], "hello");
+//! assert_eq!(slab.remove(hello), Some("hello"));
+//! // The vacant slot is reused by the next insertion.
+//! assert_eq!(slab.insert("again"), hello);
+//! assert_eq!(slab.get(world), Some(&"world"));
+//! ```
+
+use core::fmt;
+use core::iter::Enumerate;
+use core::mem::ManuallyDrop;
+use core::ops::{Index, IndexMut};
+use core::slice;
+use std::vec::Vec;
+
+use int_sentinel::IntSentinel;
+
+/// A slot of the slab, whose active field is given by the `occupied` bitset of the slab.
+union Slot<T> {
+    value: ManuallyDrop<T>,
+    /// The key of the next vacant slot.
+    next: IntSentinel,
+}
+
+impl<T> Slot<T> {
+    fn occupied(value: T) -> Self {
+        Slot { value: ManuallyDrop::new(value) }
+    }
+
+    fn vacant(next: IntSentinel) -> Self {
+        Slot { next }
+    }
+}
+
+const BITS: usize = 64;
+
+fn is_occupied(occupied: &[u64], key: usize) -> bool {
+    occupied[key / BITS] >> (key % BITS) & 1 != 0
+}
+
+/// A collection of values addressed by `usize` keys, that reuses the slots of removed values.
+///
+/// Keys are stable: a value keeps its key until it is removed, or until `compact` relocates it.
+pub struct SentinelSlab<T> {
+    slots: Vec<Slot<T>>,
+    /// One bit per slot, set if the slot holds a value.
+    occupied: Vec<u64>,
+    /// The key of the first vacant slot.
+    next: IntSentinel,
+    len: usize,
+}
+
+impl<T> SentinelSlab<T> {
+    /// Constructs a new, empty `SentinelSlab`.
+    pub fn new() -> Self {
+        SentinelSlab { slots: Vec::new(), occupied: Vec::new(), next: IntSentinel::NONE, len: 0 }
+    }
+
+    /// Constructs a new, empty `SentinelSlab` with room for `capacity` values without reallocating.
+    pub fn with_capacity(capacity: usize) -> Self {
+        SentinelSlab {
+            slots: Vec::with_capacity(capacity),
+            occupied: Vec::with_capacity(capacity.div_ceil(BITS)),
+            next: IntSentinel::NONE,
+            len: 0,
+        }
+    }
+
+    /// Returns the number of values.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if there are no values.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the number of slots, occupied or vacant.
+    pub fn slots(&self) -> usize {
+        self.slots.len()
+    }
+
+    /// Inserts `value` in the first vacant slot of the free list, or in a new slot, returning its key.
+    pub fn insert(&mut self, value: T) -> usize {
+        let key = match self.next.to_option() {
+            Some(key) => {
+                let key = key as usize;
+                // The free list only links vacant slots.
+                self.next = unsafe { self.slots[key].next };
+                self.slots[key] = Slot::occupied(value);
+                key
+            }
+            None => {
+                let key = self.slots.len();
+                if key.is_multiple_of(BITS) {
+                    self.occupied.push(0);
+                }
+                self.slots.push(Slot::occupied(value));
+                key
+            }
+        };
+        self.occupied[key / BITS] |= 1 << (key % BITS);
+        self.len += 1;
+        key
+    }
+
+    /// Removes the value of `key`, returning it, or returns `None` if the slot is vacant or out of bounds.
+    pub fn remove(&mut self, key: usize) -> Option<T> {
+        if !self.contains(key) {
+            return None;
+        }
+        self.occupied[key / BITS] &= !(1 << (key % BITS));
+        self.len -= 1;
+        // The slot was occupied, and is marked vacant before the link overwrites the value.
+        let value = unsafe { ManuallyDrop::take(&mut self.slots[key].value) };
+        self.slots[key] = Slot::vacant(self.next);
+        self.next = IntSentinel::from(Some(key as u64));
+        Some(value)
+    }
+
+    /// Returns `true` if `key` holds a value.
+    pub fn contains(&self, key: usize) -> bool {
+        key < self.slots.len() && is_occupied(&self.occupied, key)
+    }
+
+    /// Returns the value of `key`, or `None` if the slot is vacant or out of bounds.
+    pub fn get(&self, key: usize) -> Option<&T> {
+        if !self.contains(key) {
+            return None;
+        }
+        Some(unsafe { &self.slots[key].value })
+    }
+
+    /// Returns the value of `key` mutably, or `None` if the slot is vacant or out of bounds.
+    pub fn get_mut(&mut self, key: usize) -> Option<&mut T> {
+        if !self.contains(key) {
+            return None;
+        }
+        Some(unsafe { &mut self.slots[key].value })
+    }
+
+    /// Removes all the values, keeping the allocated memory.
+    pub fn clear(&mut self) {
+        self.drop_values();
+        self.slots.clear();
+        self.occupied.clear();
+        self.next = IntSentinel::NONE;
+        self.len = 0;
+    }
+
+    /// Returns an iterator over the values, yielding their key along with them, in key order.
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter { slots: self.slots.iter().enumerate(), occupied: &self.occupied }
+    }
+
+    /// Returns an iterator over the values that allows modifying them, yielding their key along with them,
+    /// in key order.
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut { slots: self.slots.iter_mut().enumerate(), occupied: &self.occupied }
+    }
+
+    /// Moves values from the end of the slab into the vacant slots, so that no slot is vacant anymore,
+    /// then releases the unused memory.
+    ///
+    /// `relocate` is called with each moved value, its previous key and its new key, so that the references
+    /// to this key can be updated.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use sentinel_int::slab::SentinelSlab;
+    /// let mut slab = SentinelSlab::new();
+    /// let keys: Vec<_> = (0..4).map(|value| slab.insert(value)).collect();
+    /// slab.remove(keys[1]);
+    /// let mut moves = Vec::new();
+    /// slab.compact(|_, from, to| moves.push((from, to)));
+    /// assert_eq!(moves, vec![(3, 1)]);
+    /// assert_eq!(slab.slots(), 3);
+    /// assert_eq!(slab[1], 3);
+    /// ```
+    pub fn compact<F: FnMut(&mut T, usize, usize)>(&mut self, mut relocate: F) {
+        // The free list is dropped before any slot is filled, so that it never links an occupied slot even if
+        // `relocate` panics: the vacant slots are then merely unlinked until the next call to `compact`.
+        self.next = IntSentinel::NONE;
+        let mut vacant = 0;
+        let mut end = self.slots.len();
+        loop {
+            while end > 0 && !self.contains(end - 1) {
+                end -= 1;
+            }
+            while vacant < end && self.contains(vacant) {
+                vacant += 1;
+            }
+            if vacant == end {
+                break;
+            }
+            end -= 1;
+            self.occupied[end / BITS] &= !(1 << (end % BITS));
+            self.len -= 1;
+            // The last slot was occupied, and is marked vacant before the unlinked marker overwrites the value.
+            let mut value = unsafe { ManuallyDrop::take(&mut self.slots[end].value) };
+            self.slots[end] = Slot::vacant(IntSentinel::NONE);
+            relocate(&mut value, end, vacant);
+            self.slots[vacant] = Slot::occupied(value);
+            self.occupied[vacant / BITS] |= 1 << (vacant % BITS);
+            self.len += 1;
+        }
+        self.slots.truncate(end);
+        self.slots.shrink_to_fit();
+        self.occupied.truncate(end.div_ceil(BITS));
+        self.occupied.shrink_to_fit();
+    }
+
+    fn drop_values(&mut self) {
+        for key in 0..self.slots.len() {
+            if is_occupied(&self.occupied, key) {
+                // The slot is marked vacant first, so that the value is never dropped twice even if a drop panics.
+                self.occupied[key / BITS] &= !(1 << (key % BITS));
+                unsafe { ManuallyDrop::drop(&mut self.slots[key].value) }
+            }
+        }
+    }
+}
+
+impl<T> Drop for SentinelSlab<T> {
+    fn drop(&mut self) {
+        self.drop_values();
+    }
+}
+
+impl<T: Clone> Clone for SentinelSlab<T> {
+    fn clone(&self) -> Self {
+        let slots = self.slots.iter().enumerate().map(|(key, slot)| {
+            if is_occupied(&self.occupied, key) {
+                Slot::occupied(unsafe { (*slot.value).clone() })
+            } else {
+                Slot::vacant(unsafe { slot.next })
+            }
+        }).collect();
+        SentinelSlab { slots, occupied: self.occupied.clone(), next: self.next, len: self.len }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for SentinelSlab<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<T> Default for SentinelSlab<T> {
+    fn default() -> Self {
+        SentinelSlab::new()
+    }
+}
+
+impl<T> Index<usize> for SentinelSlab<T> {
+    type Output = T;
+
+    /// # Panics
+    ///
+    /// This function panics if the slot of `key` is vacant or out of bounds.
+    fn index(&self, key: usize) -> &T {
+        self.get(key).expect("no value for this key")
+    }
+}
+
+impl<T> IndexMut<usize> for SentinelSlab<T> {
+    /// # Panics
+    ///
+    /// This function panics if the slot of `key` is vacant or out of bounds.
+    fn index_mut(&mut self, key: usize) -> &mut T {
+        self.get_mut(key).expect("no value for this key")
+    }
+}
+
+impl<'a, T> IntoIterator for &'a SentinelSlab<T> {
+    type Item = (usize, &'a T);
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut SentinelSlab<T> {
+    type Item = (usize, &'a mut T);
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> IterMut<'a, T> {
+        self.iter_mut()
+    }
+}
+
+/// An iterator over the values of a `SentinelSlab`, see `SentinelSlab::iter`.
+pub struct Iter<'a, T> {
+    slots: Enumerate<slice::Iter<'a, Slot<T>>>,
+    occupied: &'a [u64],
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = (usize, &'a T);
+
+    fn next(&mut self) -> Option<(usize, &'a T)> {
+        let occupied = self.occupied;
+        self.slots.by_ref()
+            .find(|&(key, _)| is_occupied(occupied, key))
+            .map(|(key, slot)| (key, unsafe { &*slot.value }))
+    }
+}
+
+impl<'a, T> Clone for Iter<'a, T> {
+    fn clone(&self) -> Self {
+        Iter { slots: self.slots.clone(), occupied: self.occupied }
+    }
+}
+
+impl<'a, T: fmt::Debug> fmt::Debug for Iter<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+/// An iterator over the values of a `SentinelSlab` that allows modifying them, see `SentinelSlab::iter_mut`.
+pub struct IterMut<'a, T> {
+    slots: Enumerate<slice::IterMut<'a, Slot<T>>>,
+    occupied: &'a [u64],
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = (usize, &'a mut T);
+
+    fn next(&mut self) -> Option<(usize, &'a mut T)> {
+        let occupied = self.occupied;
+        self.slots.by_ref()
+            .find(|&(key, _)| is_occupied(occupied, key))
+            .map(|(key, slot)| (key, unsafe { &mut *slot.value }))
+    }
+}
+
+impl<'a, T> fmt::Debug for IterMut<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("IterMut").finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::mem::size_of;
+    use std::rc::Rc;
+    use std::string::String;
+
+    use super::*;
+
+    #[test]
+    fn vacant_overhead() {
+        // A vacant slot holds the `u64` link in place of the value, so it is never larger than a value plus a `u64`.
+        assert_eq!(size_of::<Slot<u8>>(), size_of::<u64>());
+        assert_eq!(size_of::<Slot<u32>>(), size_of::<u64>());
+        assert_eq!(size_of::<Slot<u64>>(), size_of::<u64>());
+        assert_eq!(size_of::<Slot<[u64; 4]>>(), size_of::<[u64; 4]>());
+        assert_eq!(size_of::<Slot<String>>(), size_of::<String>());
+        // The occupancy bitset takes one `u64` per 64 slots.
+        let mut slab = SentinelSlab::new();
+        for value in 0..65u64 {
+            slab.insert(value);
+        }
+        assert_eq!(slab.occupied.len(), 2);
+    }
+
+    #[test]
+    fn free_list_reuse() {
+        let mut slab = SentinelSlab::new();
+        let keys: Vec<_> = (0..5).map(|value| slab.insert(value)).collect();
+        assert_eq!(keys, vec![0, 1, 2, 3, 4]);
+        assert_eq!(slab.remove(1), Some(1));
+        assert_eq!(slab.remove(3), Some(3));
+        assert_eq!(slab.remove(3), None);
+        assert_eq!(slab.remove(10), None);
+        assert_eq!(slab.len(), 3);
+        // The most recently freed slot is reused first.
+        assert_eq!(slab.insert(30), 3);
+        assert_eq!(slab.insert(10), 1);
+        assert_eq!(slab.insert(5), 5);
+        assert_eq!(slab.slots(), 6);
+        let values: Vec<_> = slab.iter().map(|(key, &value)| (key, value)).collect();
+        assert_eq!(values, vec![(0, 0), (1, 10), (2, 2), (3, 30), (4, 4), (5, 5)]);
+    }
+
+    #[test]
+    fn access() {
+        let mut slab = SentinelSlab::new();
+        let key = slab.insert(String::from("a"));
+        slab[key].push('b');
+        slab.get_mut(key).unwrap().push('c');
+        assert_eq!(slab.get(key).map(String::as_str), Some("abc"));
+        assert!(slab.contains(key));
+        for (_, value) in &mut slab {
+            value.push('d');
+        }
+        assert_eq!(slab.remove(key).as_deref(), Some("abcd"));
+        assert!(!slab.contains(key));
+        assert!(slab.is_empty());
+    }
+
+    #[test]
+    fn compact() {
+        let mut slab = SentinelSlab::new();
+        for value in 0..8 {
+            slab.insert(value);
+        }
+        for &key in &[0, 2, 3, 7] {
+            slab.remove(key);
+        }
+        let mut moves = Vec::new();
+        slab.compact(|value, from, to| {
+            assert_eq!(*value, from);
+            moves.push((from, to));
+        });
+        assert_eq!(moves, vec![(6, 0), (5, 2), (4, 3)]);
+        assert_eq!(slab.slots(), 4);
+        let values: Vec<_> = slab.iter().map(|(key, &value)| (key, value)).collect();
+        assert_eq!(values, vec![(0, 6), (1, 1), (2, 5), (3, 4)]);
+        assert_eq!(slab.insert(8), 4);
+    }
+
+    #[test]
+    fn compact_empty() {
+        let mut slab = SentinelSlab::new();
+        let key = slab.insert(1);
+        slab.remove(key);
+        slab.compact(|_, _, _| panic!("nothing to relocate"));
+        assert_eq!(slab.slots(), 0);
+        assert_eq!(slab.insert(2), 0);
+    }
+
+    #[test]
+    fn compact_panic() {
+        use std::panic::{catch_unwind, AssertUnwindSafe};
+        let mut slab = SentinelSlab::new();
+        for value in 0..4 {
+            slab.insert(value * 100);
+        }
+        slab.remove(0);
+        slab.remove(1);
+        let mut calls = 0;
+        let result = catch_unwind(AssertUnwindSafe(|| slab.compact(|_, _, _| {
+            calls += 1;
+            if calls == 2 {
+                panic!("relocation failed");
+            }
+        })));
+        assert!(result.is_err());
+        // 300 was moved to 0, 200 was dropped while unwinding.
+        assert_eq!(slab.len(), 1);
+        assert_eq!(slab.get(0), Some(&300));
+        // The vacant slots left by the panic are unlinked, so insertions append instead of reading a value as a link.
+        let keys: Vec<_> = (0..4).map(|value| slab.insert(value)).collect();
+        assert_eq!(keys, vec![4, 5, 6, 7]);
+        assert_eq!(slab.get(0), Some(&300));
+        slab.compact(|_, _, _| ());
+        assert_eq!(slab.slots(), 5);
+        assert_eq!(slab.iter().map(|(_, &value)| value).collect::<Vec<_>>(), vec![300, 3, 2, 1, 0]);
+    }
+
+    #[test]
+    fn drop_and_clone() {
+        let value = Rc::new(());
+        let mut slab = SentinelSlab::new();
+        for _ in 0..4 {
+            slab.insert(value.clone());
+        }
+        slab.remove(1);
+        assert_eq!(Rc::strong_count(&value), 4);
+        let clone = slab.clone();
+        assert_eq!(Rc::strong_count(&value), 7);
+        assert_eq!(clone.iter().map(|(key, _)| key).collect::<Vec<_>>(), vec![0, 2, 3]);
+        assert_eq!(format!("{:?}", clone), "{0: (), 2: (), 3: ()}");
+        slab.clear();
+        assert_eq!(Rc::strong_count(&value), 4);
+        drop(clone);
+        assert_eq!(Rc::strong_count(&value), 1);
+    }
+
+    #[should_panic]
+    #[test]
+    fn index_vacant() {
+        let mut slab = SentinelSlab::new();
+        let key = slab.insert(1);
+        slab.remove(key);
+        let _ = slab[key];
+    }
+}
@@ -0,0 +1,342 @@
+//! Sentinel integers that reserve the values below the sentinel as tags.
+//!
+//! A `TaggedSentinel<E>` stores either `None`, a value, or one of the `E::COUNT` states of a tag type `E`, in a single
+//! `u64`. `u64::MAX` represents `None` as with `IntSentinel`, and the tags take the `E::COUNT` values just below it,
+//! so that the values range from `0` to `max_value()`.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use sentinel_int::tagged::{SentinelTag, Tagged, TaggedSentinel};
+//!
+//! #[derive(Debug, Clone, Copy, PartialEq)]
+//! #[repr(u8)]
+//! enum Marker {
+//!     Deleted,
+//!     Pending,
+//!     Redacted,
+//! }
+//!
+//! impl SentinelTag for Marker {
+//!     const COUNT: u64 = 3;
+//!
+//!     fn to_index(self) -> u64 {
+//!         self as u64
+//!     }
+//!
+//!     fn from_index(index: u64) -> Self {
+//!         [Marker::Deleted, Marker::Pending, Marker::Redacted][index as usize]
+//!     }
+//! }
+//!
+//! let cell = TaggedSentinel::new_with_tag(Marker::Redacted);
+//! assert_eq!(cell.to_option(), Some(Tagged::Tag(Marker::Redacted)));
+//! let cell = TaggedSentinel::<Marker>::new_with_value(42);
+//! assert_eq!(cell.to_option(), Some(Tagged::Value(42)));
+//! assert_eq!(TaggedSentinel::<Marker>::max_value(), u64::MAX - 4);
+//! ```
+
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::marker::PhantomData;
+
+use error::SentinelError;
+
+/// A type with `COUNT` states that can be stored in the reserved values of a `TaggedSentinel`.
+///
+/// This is typically implemented by a field-less enum, mapping each variant to its discriminant.
+pub trait SentinelTag: Copy {
+    /// The number of states, i.e. the number of reserved values.
+    const COUNT: u64;
+
+    /// Returns the index of this state, which must be lower than `COUNT`.
+    fn to_index(self) -> u64;
+
+    /// Returns the state of `index`.
+    ///
+    /// This function is never called with an index greater than or equal to `COUNT` by `TaggedSentinel`.
+    fn from_index(index: u64) -> Self;
+}
+
+/// The contents of a `TaggedSentinel` that is not `None`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Tagged<E> {
+    /// A value, lower than or equal to `TaggedSentinel::max_value()`.
+    Value(u64),
+    /// A tag.
+    Tag(E),
+}
+
+/// A compact representation for `Option<Tagged<E>>`, using `u64::MAX` for `None`
+/// and the `E::COUNT` values below it for the tags.
+///
+/// # Layout
+///
+/// This type is guaranteed to have the same layout as `u64`, and every `u64` bit pattern is a valid instance.
+#[repr(transparent)]
+pub struct TaggedSentinel<E> {
+    value: u64,
+    tag: PhantomData<fn() -> E>,
+}
+
+impl<E: SentinelTag> TaggedSentinel<E> {
+    /// An instance containing `None`, see `new_none()`.
+    pub const NONE: Self = TaggedSentinel { value: u64::MAX, tag: PhantomData };
+
+    /// The maximum value that can be represented by this type, see `max_value()`.
+    pub const MAX: u64 = u64::MAX - 1 - E::COUNT;
+
+    /// The maximum value that can be represented by this type, i.e. the value below the reserved ones.
+    pub const fn max_value() -> u64 {
+        Self::MAX
+    }
+
+    /// Returns `true` if `value` can be stored as a `Tagged::Value`, i.e. if it is not one of the reserved values.
+    pub const fn is_valid(value: u64) -> bool {
+        value <= Self::MAX
+    }
+
+    /// Constructs a new `TaggedSentinel` containing `None`.
+    pub const fn new_none() -> Self {
+        Self::NONE
+    }
+
+    /// Constructs a new `TaggedSentinel` containing the provided value.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `value` is not valid (i.e., if it is greater than `max_value()`).
+    /// When evaluated in a `const` context, the panic is reported as a compilation error.
+    pub const fn new_with_value(value: u64) -> Self {
+        match TaggedSentinel::try_new_with_value(value) {
+            Ok(sentinel) => sentinel,
+            Err(_) => panic!("Illegal value: the reserved values cannot be stored as a `Tagged::Value`."),
+        }
+    }
+
+    /// Constructs a new `TaggedSentinel` containing the provided value,
+    /// or returns an error if `value` is not valid (i.e., if it is greater than `max_value()`).
+    pub const fn try_new_with_value(value: u64) -> Result<Self, SentinelError<u64>> {
+        if !Self::is_valid(value) {
+            return Err(SentinelError::new(value));
+        }
+        Ok(TaggedSentinel { value, tag: PhantomData })
+    }
+
+    /// Constructs a new `TaggedSentinel` containing the provided tag.
+    ///
+    /// Unlike `new_with_value`, this function is not `const`, since it calls `SentinelTag::to_index`.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the index of `tag` is not lower than `E::COUNT`, i.e. if `E` is wrongly implemented.
+    pub fn new_with_tag(tag: E) -> Self {
+        let index = tag.to_index();
+        assert!(index < E::COUNT, "tag index {} is out of range", index);
+        TaggedSentinel { value: u64::MAX - 1 - index, tag: PhantomData }
+    }
+
+    /// Constructs a new `TaggedSentinel` from an `Option<Tagged<E>>`,
+    /// or returns an error if it contains a value that is not valid.
+    ///
+    /// This is the fallible counterpart of `From<Option<Tagged<E>>>`.
+    pub fn try_from_option(option: Option<Tagged<E>>) -> Result<Self, SentinelError<u64>> {
+        match option {
+            Some(Tagged::Value(value)) => TaggedSentinel::try_new_with_value(value),
+            Some(Tagged::Tag(tag)) => Ok(TaggedSentinel::new_with_tag(tag)),
+            None => Ok(TaggedSentinel::new_none()),
+        }
+    }
+
+    /// Returns an `Option<Tagged<E>>` corresponding to the contents of this instance.
+    pub fn to_option(&self) -> Option<Tagged<E>> {
+        if self.value == u64::MAX {
+            None
+        } else if self.value > Self::MAX {
+            Some(Tagged::Tag(E::from_index(u64::MAX - 1 - self.value)))
+        } else {
+            Some(Tagged::Value(self.value))
+        }
+    }
+
+    /// Returns the contained value, or `None` if this instance contains `None` or a tag.
+    pub const fn value(&self) -> Option<u64> {
+        if Self::is_valid(self.value) {
+            Some(self.value)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the contained tag, or `None` if this instance contains `None` or a value.
+    pub fn tag(&self) -> Option<E> {
+        match self.to_option() {
+            Some(Tagged::Tag(tag)) => Some(tag),
+            _ => None,
+        }
+    }
+
+    /// Returns `true` if this instance contains `None`.
+    pub const fn is_none(&self) -> bool {
+        self.value == u64::MAX
+    }
+
+    /// Returns `true` if this instance contains a tag.
+    pub const fn is_tag(&self) -> bool {
+        self.value != u64::MAX && self.value > Self::MAX
+    }
+
+    /// Constructs a new `TaggedSentinel` from a raw value without checking the reserved values.
+    ///
+    /// # Safety
+    ///
+    /// If using this function to create a `TaggedSentinel`, `u64::MAX` will be transformed into a `None` value,
+    /// the values between `max_value()` and `u64::MAX` into the corresponding tags, and any other value will be
+    /// mapped to a `Tagged::Value` of this value.
+    pub const unsafe fn unchecked_new(value: u64) -> Self {
+        TaggedSentinel { value, tag: PhantomData }
+    }
+
+    /// Returns the raw contained value without a check.
+    ///
+    /// # Safety
+    ///
+    /// This method returns `u64::MAX` when the instance contains `None`, `u64::MAX - 1 - tag.to_index()` when it
+    /// contains a tag, and the contained value otherwise.
+    pub const unsafe fn to_u64_unchecked(&self) -> u64 {
+        self.value
+    }
+}
+
+impl<E> Clone for TaggedSentinel<E> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<E> Copy for TaggedSentinel<E> {}
+
+impl<E> PartialEq for TaggedSentinel<E> {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl<E> Eq for TaggedSentinel<E> {}
+
+impl<E> Hash for TaggedSentinel<E> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.value.hash(state)
+    }
+}
+
+impl<E: SentinelTag> Default for TaggedSentinel<E> {
+    fn default() -> Self {
+        TaggedSentinel::new_none()
+    }
+}
+
+impl<E: SentinelTag + fmt::Debug> fmt::Debug for TaggedSentinel<E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("TaggedSentinel").field(&self.to_option()).finish()
+    }
+}
+
+impl<E: SentinelTag> From<Option<Tagged<E>>> for TaggedSentinel<E> {
+    /// # Panics
+    ///
+    /// This function panics if `option` contains a value that is not valid.
+    fn from(option: Option<Tagged<E>>) -> Self {
+        match TaggedSentinel::try_from_option(option) {
+            Ok(sentinel) => sentinel,
+            Err(error) => panic!("{}", error),
+        }
+    }
+}
+
+impl<E: SentinelTag> From<TaggedSentinel<E>> for Option<Tagged<E>> {
+    fn from(sentinel: TaggedSentinel<E>) -> Self {
+        sentinel.to_option()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::mem::size_of;
+
+    use super::*;
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    enum State {
+        Deleted,
+        Pending,
+    }
+
+    impl SentinelTag for State {
+        const COUNT: u64 = 2;
+
+        fn to_index(self) -> u64 {
+            self as u64
+        }
+
+        fn from_index(index: u64) -> Self {
+            match index {
+                0 => State::Deleted,
+                _ => State::Pending,
+            }
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let options = [
+            None,
+            Some(Tagged::Tag(State::Deleted)),
+            Some(Tagged::Tag(State::Pending)),
+            Some(Tagged::Value(0)),
+            Some(Tagged::Value(u64::MAX - 3)),
+        ];
+        for &option in options.iter() {
+            let sentinel = TaggedSentinel::from(option);
+            assert_eq!(sentinel.to_option(), option);
+            assert_eq!(Option::<Tagged<State>>::from(sentinel), option);
+        }
+        assert_eq!(size_of::<TaggedSentinel<State>>(), size_of::<u64>());
+    }
+
+    #[test]
+    fn raw_values() {
+        let deleted = TaggedSentinel::new_with_tag(State::Deleted);
+        let pending = TaggedSentinel::new_with_tag(State::Pending);
+        assert_eq!(unsafe { deleted.to_u64_unchecked() }, u64::MAX - 1);
+        assert_eq!(unsafe { pending.to_u64_unchecked() }, u64::MAX - 2);
+        assert_eq!(unsafe { TaggedSentinel::<State>::NONE.to_u64_unchecked() }, u64::MAX);
+        assert_eq!(TaggedSentinel::<State>::max_value(), u64::MAX - 3);
+        assert!(pending.is_tag());
+        assert_eq!(pending.tag(), Some(State::Pending));
+        assert_eq!(pending.value(), None);
+        assert!(TaggedSentinel::<State>::default().is_none());
+        assert!(!TaggedSentinel::<State>::NONE.is_tag());
+    }
+
+    #[test]
+    fn checked_construction() {
+        let error = TaggedSentinel::<State>::try_new_with_value(u64::MAX - 2).unwrap_err();
+        assert_eq!(error.value(), u64::MAX - 2);
+        assert!(TaggedSentinel::<State>::try_from_option(Some(Tagged::Value(u64::MAX))).is_err());
+        assert_eq!(TaggedSentinel::<State>::new_with_value(7).value(), Some(7));
+    }
+
+    #[test]
+    fn const_table() {
+        static TABLE: [TaggedSentinel<State>; 2] = [TaggedSentinel::NONE, TaggedSentinel::new_with_value(42)];
+        assert_eq!(TABLE[0].to_option(), None);
+        assert_eq!(TABLE[1].to_option(), Some(Tagged::Value(42)));
+    }
+
+    #[should_panic]
+    #[test]
+    fn illegal_value() {
+        TaggedSentinel::<State>::new_with_value(u64::MAX - 1);
+    }
+}
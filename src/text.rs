@@ -0,0 +1,114 @@
+//! Textual representation of the sentinel integers.
+//!
+//! The sentinel integers implement `Display`, writing their value or `DEFAULT_NULL` for `None`,
+//! and `FromStr`, accepting decimal values, unsigned hexadecimal values prefixed with `0x` (so `0x-5` is an error),
+//! and any of `NULL_TOKENS` for `None`. Use `display_with` and `parse_with_nulls` to choose other null spellings.
+//!
+//! # Examples
+//!
+//! ```rust
+//! # use sentinel_int::int_sentinel::IntSentinel;
+//! let sentinel: IntSentinel = "0x2a".parse().unwrap();
+//! assert_eq!(sentinel.to_string(), "42");
+//! let sentinel: IntSentinel = "NULL".parse().unwrap();
+//! assert_eq!(sentinel.to_string(), "null");
+//! assert_eq!(sentinel.display_with("NA").to_string(), "NA");
+//! ```
+
+use core::fmt;
+use core::num::ParseIntError;
+#[cfg(feature = "std")]
+use std::error::Error;
+
+use error::SentinelError;
+
+/// The string written by `Display` for `None`.
+pub const DEFAULT_NULL: &str = "null";
+
+/// The strings accepted by `FromStr` for `None`, compared ASCII case-insensitively.
+pub const NULL_TOKENS: &[&str] = &["", "null", "none", "-"];
+
+/// The error returned when parsing a sentinel integer fails.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseSentinelError<T> {
+    /// The string is neither a null token nor a valid integer.
+    Int(ParseIntError),
+    /// The string is the sentinel value, which cannot be parsed as a `Some`.
+    Sentinel(SentinelError<T>),
+}
+
+impl<T: fmt::Debug> fmt::Display for ParseSentinelError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ParseSentinelError::Int(ref error) => fmt::Display::fmt(error, f),
+            ParseSentinelError::Sentinel(ref error) => fmt::Display::fmt(error, f),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: fmt::Debug + 'static> Error for ParseSentinelError<T> {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            ParseSentinelError::Int(ref error) => Some(error),
+            ParseSentinelError::Sentinel(ref error) => Some(error),
+        }
+    }
+}
+
+impl<T> From<ParseIntError> for ParseSentinelError<T> {
+    fn from(error: ParseIntError) -> Self {
+        ParseSentinelError::Int(error)
+    }
+}
+
+impl<T> From<SentinelError<T>> for ParseSentinelError<T> {
+    fn from(error: SentinelError<T>) -> Self {
+        ParseSentinelError::Sentinel(error)
+    }
+}
+
+/// Displays an optional value, writing a custom null token for `None`.
+///
+/// Returned by the `display_with` method of the sentinel integers. Formatting options such as the width apply
+/// to the null token as well as to the value.
+#[derive(Debug, Clone, Copy)]
+pub struct DisplayWith<'a, T> {
+    value: Option<T>,
+    null: &'a str,
+}
+
+impl<'a, T> DisplayWith<'a, T> {
+    pub(crate) fn new(value: Option<T>, null: &'a str) -> Self {
+        DisplayWith { value, null }
+    }
+}
+
+impl<'a, T: fmt::Display> fmt::Display for DisplayWith<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.value {
+            Some(ref value) => fmt::Display::fmt(value, f),
+            None => f.pad(self.null),
+        }
+    }
+}
+
+/// Returns `true` if `s` is one of `null_tokens`, compared ASCII case-insensitively.
+pub(crate) fn is_null_token(s: &str, null_tokens: &[&str]) -> bool {
+    null_tokens.iter().any(|token| token.eq_ignore_ascii_case(s))
+}
+
+/// Returns the hexadecimal digits of `s` if it starts with `0x` or `0X`.
+///
+/// The digits are rejected if they start with a sign, which `from_str_radix` would otherwise accept.
+pub(crate) fn strip_hex_prefix(s: &str) -> Result<Option<&str>, ParseIntError> {
+    if !(s.starts_with("0x") || s.starts_with("0X")) {
+        return Ok(None);
+    }
+    let digits = &s[2..];
+    if digits.starts_with('+') || digits.starts_with('-') {
+        // `ParseIntError` cannot be constructed directly: parse the sign alone to get an `InvalidDigit` error.
+        return Err(digits[..1].parse::<u8>().unwrap_err());
+    }
+    Ok(Some(digits))
+}
@@ -0,0 +1,409 @@
+//! Optional indices that are tagged with the collection they index.
+//!
+//! A `SentinelIndex<Tag>` is an optional `usize` index, stored in a single `usize` with `usize::MAX` as the sentinel.
+//! The `Tag` type parameter is never instantiated: it only names the table the index points into, so that an index
+//! into one `TypedVec<Tag, T>` cannot be used with a `TypedVec` of a different tag.
+//!
+//! # Examples
+//!
+#![cfg_attr(feature = "std", doc = "```rust")]
+#![cfg_attr(not(feature = "std"), doc = "```ignore")]
+//! use sentinel_int::typed_index::{SentinelIndex, TypedVec};
+//! enum Users {}
+//! enum Groups {}
+//!
+//! let mut users: TypedVec<Users, &str> = TypedVec::new();
+//! let mut groups: TypedVec<Groups, &str> = TypedVec::new();
+//! let alice = users.push("alice");
+//! let admins = groups.push("admins");
+//! assert_eq!(users[alice], "alice");
+//! assert_eq!(groups[admins], "admins");
+//!
+//! let manager: SentinelIndex<Users> = SentinelIndex::NONE;
+//! assert_eq!(users.get(manager), None);
+//! ```
+//!
+//! Indexing with an index of another table does not compile:
+//!
+#![cfg_attr(feature = "std", doc = "```rust,compile_fail")]
+#![cfg_attr(not(feature = "std"), doc = "```ignore")]
+//! # use sentinel_int::typed_index::TypedVec;
+//! # enum Users {}
+//! # enum Groups {}
+//! let mut users: TypedVec<Users, &str> = TypedVec::new();
+//! let groups: TypedVec<Groups, &str> = TypedVec::new();
+//! let alice = users.push("alice");
+//! let _ = groups[alice];
+//! ```
+
+use core::cmp::Ordering;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+#[cfg(feature = "std")]
+use core::iter::FromIterator;
+use core::marker::PhantomData;
+#[cfg(feature = "std")]
+use core::ops::{Index, IndexMut};
+#[cfg(feature = "std")]
+use core::slice;
+#[cfg(feature = "std")]
+use std::vec::Vec;
+
+use error::SentinelError;
+use int_sentinel::IntSentinelUsize;
+
+/// An optional index into a collection tagged with `Tag`, stored as an `IntSentinelUsize`.
+///
+/// `SentinelIndex<Tag>` has the size of a `usize`, and implements the common traits regardless of `Tag`.
+#[repr(transparent)]
+pub struct SentinelIndex<Tag> {
+    value: IntSentinelUsize,
+    tag: PhantomData<fn() -> Tag>,
+}
+
+impl<Tag> SentinelIndex<Tag> {
+    /// The index containing `None`.
+    pub const NONE: Self = SentinelIndex::new_none();
+
+    /// Constructs a new `SentinelIndex` containing `None`.
+    pub const fn new_none() -> Self {
+        SentinelIndex { value: IntSentinelUsize::NONE, tag: PhantomData }
+    }
+
+    /// Constructs a new `SentinelIndex` containing the provided index.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `index` is `usize::MAX`.
+    pub const fn new_with_some(index: usize) -> Self {
+        SentinelIndex { value: IntSentinelUsize::new_with_some(index), tag: PhantomData }
+    }
+
+    /// Constructs a new `SentinelIndex` containing the provided index,
+    /// or returns an error if `index` is `usize::MAX`.
+    pub const fn try_new_with_some(index: usize) -> Result<Self, SentinelError<usize>> {
+        match IntSentinelUsize::try_new_with_some(index) {
+            Ok(value) => Ok(SentinelIndex { value, tag: PhantomData }),
+            Err(error) => Err(error),
+        }
+    }
+
+    /// Constructs a new `SentinelIndex` from an untagged sentinel index.
+    pub const fn from_sentinel(value: IntSentinelUsize) -> Self {
+        SentinelIndex { value, tag: PhantomData }
+    }
+
+    /// Returns the untagged sentinel index.
+    pub const fn to_sentinel(self) -> IntSentinelUsize {
+        self.value
+    }
+
+    /// Returns an `Option` corresponding to the index contained in this instance.
+    pub const fn to_option(self) -> Option<usize> {
+        self.value.to_option()
+    }
+
+    /// Returns `true` if this instance contains an index.
+    pub const fn is_some(self) -> bool {
+        self.value.is_some()
+    }
+
+    /// Returns `true` if this instance contains `None`.
+    pub const fn is_none(self) -> bool {
+        self.value.is_none()
+    }
+}
+
+impl<Tag> Clone for SentinelIndex<Tag> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<Tag> Copy for SentinelIndex<Tag> {}
+
+impl<Tag> PartialEq for SentinelIndex<Tag> {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl<Tag> Eq for SentinelIndex<Tag> {}
+
+impl<Tag> PartialOrd for SentinelIndex<Tag> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<Tag> Ord for SentinelIndex<Tag> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.value.cmp(&other.value)
+    }
+}
+
+impl<Tag> Hash for SentinelIndex<Tag> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.value.hash(state)
+    }
+}
+
+impl<Tag> Default for SentinelIndex<Tag> {
+    fn default() -> Self {
+        SentinelIndex::new_none()
+    }
+}
+
+impl<Tag> fmt::Debug for SentinelIndex<Tag> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("SentinelIndex").field(&self.to_option()).finish()
+    }
+}
+
+impl<Tag> From<Option<usize>> for SentinelIndex<Tag> {
+    fn from(option: Option<usize>) -> Self {
+        SentinelIndex::from_sentinel(IntSentinelUsize::from(option))
+    }
+}
+
+impl<Tag> From<SentinelIndex<Tag>> for Option<usize> {
+    fn from(index: SentinelIndex<Tag>) -> Self {
+        index.to_option()
+    }
+}
+
+/// A `Vec<T>` that can only be indexed with a `SentinelIndex<Tag>`.
+///
+/// Indexing with `[]` panics when the index is `None` or out of bounds, use `get` to handle both cases.
+#[cfg(feature = "std")]
+pub struct TypedVec<Tag, T> {
+    values: Vec<T>,
+    tag: PhantomData<fn() -> Tag>,
+}
+
+#[cfg(feature = "std")]
+impl<Tag, T> TypedVec<Tag, T> {
+    /// Constructs a new, empty `TypedVec`.
+    pub fn new() -> Self {
+        TypedVec::from(Vec::new())
+    }
+
+    /// Constructs a new, empty `TypedVec` with room for `capacity` elements without reallocating.
+    pub fn with_capacity(capacity: usize) -> Self {
+        TypedVec::from(Vec::with_capacity(capacity))
+    }
+
+    /// Returns the number of elements.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Returns `true` if there are no elements.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Appends an element, returning its index.
+    pub fn push(&mut self, value: T) -> SentinelIndex<Tag> {
+        let index = SentinelIndex::new_with_some(self.values.len());
+        self.values.push(value);
+        index
+    }
+
+    /// Returns the element at `index`, or `None` if `index` is `None` or out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use sentinel_int::typed_index::{SentinelIndex, TypedVec};
+    /// let values: TypedVec<(), u32> = vec![1, 2].into();
+    /// assert_eq!(values.get(SentinelIndex::new_with_some(1)), Some(&2));
+    /// assert_eq!(values.get(SentinelIndex::new_with_some(2)), None);
+    /// assert_eq!(values.get(SentinelIndex::NONE), None);
+    /// ```
+    pub fn get(&self, index: SentinelIndex<Tag>) -> Option<&T> {
+        index.to_option().and_then(|index| self.values.get(index))
+    }
+
+    /// Returns the element at `index` mutably, or `None` if `index` is `None` or out of bounds.
+    pub fn get_mut(&mut self, index: SentinelIndex<Tag>) -> Option<&mut T> {
+        match index.to_option() {
+            Some(index) => self.values.get_mut(index),
+            None => None,
+        }
+    }
+
+    /// Returns an iterator over the elements.
+    pub fn iter(&self) -> slice::Iter<'_, T> {
+        self.values.iter()
+    }
+
+    /// Returns an iterator over the elements, that allows modifying them.
+    pub fn iter_mut(&mut self) -> slice::IterMut<'_, T> {
+        self.values.iter_mut()
+    }
+
+    /// Returns an iterator over the elements, yielding their index along with them.
+    pub fn iter_indexed(&self) -> impl Iterator<Item = (SentinelIndex<Tag>, &T)> {
+        self.values.iter().enumerate().map(|(index, value)| (SentinelIndex::new_with_some(index), value))
+    }
+
+    /// Returns the elements as a slice.
+    pub fn as_slice(&self) -> &[T] {
+        &self.values
+    }
+
+    /// Returns the elements as a mutable slice.
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        &mut self.values
+    }
+}
+
+#[cfg(feature = "std")]
+impl<Tag, T> Index<SentinelIndex<Tag>> for TypedVec<Tag, T> {
+    type Output = T;
+
+    /// # Panics
+    ///
+    /// This function panics if `index` is `None` or out of bounds.
+    fn index(&self, index: SentinelIndex<Tag>) -> &T {
+        &self.values[index.to_option().expect("indexed a `TypedVec` with a `None` index")]
+    }
+}
+
+#[cfg(feature = "std")]
+impl<Tag, T> IndexMut<SentinelIndex<Tag>> for TypedVec<Tag, T> {
+    /// # Panics
+    ///
+    /// This function panics if `index` is `None` or out of bounds.
+    fn index_mut(&mut self, index: SentinelIndex<Tag>) -> &mut T {
+        &mut self.values[index.to_option().expect("indexed a `TypedVec` with a `None` index")]
+    }
+}
+
+#[cfg(feature = "std")]
+impl<Tag, T: Clone> Clone for TypedVec<Tag, T> {
+    fn clone(&self) -> Self {
+        TypedVec::from(self.values.clone())
+    }
+}
+
+#[cfg(feature = "std")]
+impl<Tag, T: PartialEq> PartialEq for TypedVec<Tag, T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.values == other.values
+    }
+}
+
+#[cfg(feature = "std")]
+impl<Tag, T: Eq> Eq for TypedVec<Tag, T> {}
+
+#[cfg(feature = "std")]
+impl<Tag, T: Hash> Hash for TypedVec<Tag, T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.values.hash(state)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<Tag, T> Default for TypedVec<Tag, T> {
+    fn default() -> Self {
+        TypedVec::new()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<Tag, T: fmt::Debug> fmt::Debug for TypedVec<Tag, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(&self.values, f)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<Tag, T> From<Vec<T>> for TypedVec<Tag, T> {
+    fn from(values: Vec<T>) -> Self {
+        TypedVec { values, tag: PhantomData }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<Tag, T> From<TypedVec<Tag, T>> for Vec<T> {
+    fn from(vec: TypedVec<Tag, T>) -> Self {
+        vec.values
+    }
+}
+
+#[cfg(feature = "std")]
+impl<Tag, T> FromIterator<T> for TypedVec<Tag, T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        TypedVec::from(iter.into_iter().collect::<Vec<_>>())
+    }
+}
+
+#[cfg(feature = "std")]
+impl<Tag, T> Extend<T> for TypedVec<Tag, T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.values.extend(iter)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'a, Tag, T> IntoIterator for &'a TypedVec<Tag, T> {
+    type Item = &'a T;
+    type IntoIter = slice::Iter<'a, T>;
+
+    fn into_iter(self) -> slice::Iter<'a, T> {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::mem::size_of;
+
+    use super::*;
+
+    enum Nodes {}
+
+    #[test]
+    fn size() {
+        assert_eq!(size_of::<SentinelIndex<Nodes>>(), size_of::<usize>());
+    }
+
+    #[test]
+    fn index() {
+        let none: SentinelIndex<Nodes> = SentinelIndex::default();
+        assert!(none.is_none());
+        assert_eq!(none, SentinelIndex::from(None));
+        let some: SentinelIndex<Nodes> = SentinelIndex::from(Some(3));
+        assert_eq!(some.to_option(), Some(3));
+        assert_eq!(Option::<usize>::from(some), Some(3));
+        assert!(none < some);
+        assert_eq!(SentinelIndex::<Nodes>::try_new_with_some(usize::MAX).unwrap_err().value(), usize::MAX);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn typed_vec() {
+        let mut nodes: TypedVec<Nodes, u32> = TypedVec::new();
+        let first = nodes.push(1);
+        let second = nodes.push(2);
+        nodes[second] += 40;
+        assert_eq!(nodes[first], 1);
+        assert_eq!(nodes.get(second), Some(&42));
+        *nodes.get_mut(first).unwrap() = 7;
+        assert_eq!(nodes.as_slice(), &[7, 42]);
+        assert_eq!(nodes.get(SentinelIndex::NONE), None);
+        assert_eq!(nodes.get_mut(SentinelIndex::new_with_some(2)), None);
+        let indexed: Vec<_> = nodes.iter_indexed().map(|(index, &value)| (index.to_option(), value)).collect();
+        assert_eq!(indexed, vec![(Some(0), 7), (Some(1), 42)]);
+    }
+
+    #[cfg(feature = "std")]
+    #[should_panic]
+    #[test]
+    fn index_none() {
+        let nodes: TypedVec<Nodes, u32> = vec![1].into();
+        let _ = nodes[SentinelIndex::NONE];
+    }
+}
@@ -0,0 +1,337 @@
+//! Variable-length encoding of `IntSentinel`, based on unsigned LEB128.
+//!
+//! Values are shifted so that `None` is encoded as `0` and takes a single byte, as do the values up to 126:
+//! with the default sentinel, `None` is encoded as `0` and `Some(x)` as `x + 1`.
+//! More generally, the raw value is encoded as `raw.wrapping_sub(SENTINEL)`, so that `None` is always `0`.
+//!
+//! Decoding rejects truncated input and overlong input, i.e. input longer than `MAX_LEN` bytes, overflowing
+//! a `u64`, or not using the shortest possible encoding.
+//!
+//! # Examples
+//!
+//! ```rust
+//! # use sentinel_int::int_sentinel::IntSentinel;
+//! use sentinel_int::varint;
+//! let mut buf = [0; varint::MAX_LEN];
+//! assert_eq!(varint::encode(IntSentinel::NONE, &mut buf), 1);
+//! assert_eq!(buf[0], 0x00);
+//! let len = varint::encode(IntSentinel::from(Some(300)), &mut buf);
+//! assert_eq!(&buf[..len], [0xad, 0x02]);
+//!
+//! let (value, len): (IntSentinel, _) = varint::decode(&buf).unwrap();
+//! assert_eq!((value.to_option(), len), (Some(300), 2));
+//! ```
+
+use core::fmt;
+#[cfg(feature = "std")]
+use std::error::Error;
+#[cfg(feature = "std")]
+use std::io;
+#[cfg(feature = "std")]
+use std::vec::Vec;
+
+use int_sentinel::IntSentinelWith;
+
+/// The maximum length of an encoded value, in bytes.
+pub const MAX_LEN: usize = 10;
+
+/// The error returned when decoding invalid input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum VarintError {
+    /// The input ended in the middle of a value.
+    Truncated,
+    /// The value is encoded with more bytes than necessary, or overflows a `u64`.
+    Overlong,
+}
+
+impl fmt::Display for VarintError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            VarintError::Truncated => write!(f, "Truncated varint."),
+            VarintError::Overlong => write!(f, "Overlong varint."),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for VarintError {}
+
+#[cfg(feature = "std")]
+impl From<VarintError> for io::Error {
+    fn from(error: VarintError) -> Self {
+        let kind = match error {
+            VarintError::Truncated => io::ErrorKind::UnexpectedEof,
+            VarintError::Overlong => io::ErrorKind::InvalidData,
+        };
+        io::Error::new(kind, error)
+    }
+}
+
+fn to_key<const SENTINEL: u64>(value: IntSentinelWith<SENTINEL>) -> u64 {
+    unsafe { value.to_u64_unchecked() }.wrapping_sub(SENTINEL)
+}
+
+fn from_key<const SENTINEL: u64>(key: u64) -> IntSentinelWith<SENTINEL> {
+    unsafe { IntSentinelWith::unchecked_new(key.wrapping_add(SENTINEL)) }
+}
+
+/// Accumulates the bytes of a single value.
+struct Decoder {
+    key: u64,
+    len: usize,
+}
+
+impl Decoder {
+    fn new() -> Self {
+        Decoder { key: 0, len: 0 }
+    }
+
+    /// Feeds a byte, returns the decoded key if it was the last byte of the value.
+    fn push(&mut self, byte: u8) -> Result<Option<u64>, VarintError> {
+        let payload = u64::from(byte & 0x7f);
+        let shift = 7 * self.len;
+        self.len += 1;
+        if self.len == MAX_LEN && payload > 1 {
+            return Err(VarintError::Overlong);
+        }
+        self.key |= payload << shift;
+        if byte & 0x80 != 0 {
+            if self.len == MAX_LEN {
+                return Err(VarintError::Overlong);
+            }
+            Ok(None)
+        } else if byte == 0 && self.len > 1 {
+            Err(VarintError::Overlong)
+        } else {
+            Ok(Some(self.key))
+        }
+    }
+}
+
+/// Returns the number of bytes needed to encode `value`.
+pub fn encoded_len<const SENTINEL: u64>(value: IntSentinelWith<SENTINEL>) -> usize {
+    let bits = 64 - to_key(value).leading_zeros() as usize;
+    if bits == 0 {
+        1
+    } else {
+        bits.div_ceil(7)
+    }
+}
+
+/// Encodes `value` at the start of `buf`, returns the number of bytes written.
+///
+/// # Panics
+///
+/// This function panics if `buf` is shorter than `encoded_len(value)`. A buffer of `MAX_LEN` bytes is always enough.
+pub fn encode<const SENTINEL: u64>(value: IntSentinelWith<SENTINEL>, buf: &mut [u8]) -> usize {
+    let mut key = to_key(value);
+    let mut len = 0;
+    loop {
+        let byte = (key & 0x7f) as u8;
+        key >>= 7;
+        if key == 0 {
+            buf[len] = byte;
+            return len + 1;
+        }
+        buf[len] = byte | 0x80;
+        len += 1;
+    }
+}
+
+/// Decodes a value from the start of `buf`, returns it along with the number of bytes read.
+///
+/// # Examples
+///
+/// ```rust
+/// # use sentinel_int::int_sentinel::IntSentinel;
+/// # use sentinel_int::varint::{self, VarintError};
+/// let (value, len): (IntSentinel, _) = varint::decode(&[0x2b, 0xff]).unwrap();
+/// assert_eq!((value.to_option(), len), (Some(42), 1));
+/// assert_eq!(varint::decode::<{ u64::MAX }>(&[0x80]), Err(VarintError::Truncated));
+/// assert_eq!(varint::decode::<{ u64::MAX }>(&[0x80, 0x00]), Err(VarintError::Overlong));
+/// ```
+pub fn decode<const SENTINEL: u64>(buf: &[u8]) -> Result<(IntSentinelWith<SENTINEL>, usize), VarintError> {
+    let mut decoder = Decoder::new();
+    for &byte in buf {
+        if let Some(key) = decoder.push(byte)? {
+            return Ok((from_key(key), decoder.len));
+        }
+    }
+    Err(VarintError::Truncated)
+}
+
+/// Encodes every element of `values` and appends them to `out`.
+///
+/// # Examples
+///
+/// ```rust
+/// # use sentinel_int::int_sentinel::IntSentinel;
+/// use sentinel_int::varint;
+/// let values: [IntSentinel; 3] = [None.into(), Some(42).into(), Some(300).into()];
+/// let mut buf = Vec::new();
+/// varint::encode_slice(&values, &mut buf);
+/// assert_eq!(buf, [0x00, 0x2b, 0xad, 0x02]);
+///
+/// let mut decoded: Vec<IntSentinel> = Vec::new();
+/// varint::decode_slice(&buf, &mut decoded).unwrap();
+/// assert_eq!(decoded, values);
+/// ```
+#[cfg(feature = "std")]
+pub fn encode_slice<const SENTINEL: u64>(values: &[IntSentinelWith<SENTINEL>], out: &mut Vec<u8>) {
+    let mut buf = [0; MAX_LEN];
+    for &value in values {
+        let len = encode(value, &mut buf);
+        out.extend_from_slice(&buf[..len]);
+    }
+}
+
+/// Decodes all the values contained in `buf` and appends them to `out`.
+///
+/// When an error is returned, the values decoded before the error have been appended to `out`.
+#[cfg(feature = "std")]
+pub fn decode_slice<const SENTINEL: u64>(
+    mut buf: &[u8],
+    out: &mut Vec<IntSentinelWith<SENTINEL>>,
+) -> Result<(), VarintError> {
+    while !buf.is_empty() {
+        let (value, len) = decode(buf)?;
+        out.push(value);
+        buf = &buf[len..];
+    }
+    Ok(())
+}
+
+/// Encodes `value` to `writer`, returns the number of bytes written.
+#[cfg(feature = "std")]
+pub fn write<W: io::Write, const SENTINEL: u64>(writer: &mut W, value: IntSentinelWith<SENTINEL>) -> io::Result<usize> {
+    let mut buf = [0; MAX_LEN];
+    let len = encode(value, &mut buf);
+    writer.write_all(&buf[..len])?;
+    Ok(len)
+}
+
+/// Decodes a value from `reader`, reading exactly the bytes of the value.
+///
+/// Truncated input is reported as an `UnexpectedEof` error, overlong input as an `InvalidData` error
+/// wrapping a `VarintError`.
+///
+/// # Examples
+///
+/// ```rust
+/// # use sentinel_int::int_sentinel::IntSentinelWith;
+/// # use sentinel_int::varint;
+/// let mut buf = Vec::new();
+/// varint::write(&mut buf, IntSentinelWith::<0>::from(Some(u64::MAX))).unwrap();
+/// let value: IntSentinelWith<0> = varint::read(&mut &buf[..]).unwrap();
+/// assert_eq!(value.to_option(), Some(u64::MAX));
+/// ```
+#[cfg(feature = "std")]
+pub fn read<R: io::Read, const SENTINEL: u64>(reader: &mut R) -> io::Result<IntSentinelWith<SENTINEL>> {
+    let mut decoder = Decoder::new();
+    loop {
+        let mut byte = [0];
+        if let Err(error) = reader.read_exact(&mut byte) {
+            return Err(if error.kind() == io::ErrorKind::UnexpectedEof {
+                VarintError::Truncated.into()
+            } else {
+                error
+            });
+        }
+        if let Some(key) = decoder.push(byte[0])? {
+            return Ok(from_key(key));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use int_sentinel::IntSentinel;
+
+    use super::*;
+
+    fn round_trip<const SENTINEL: u64>(option: Option<u64>, expected_len: usize) {
+        let value = IntSentinelWith::<SENTINEL>::from(option);
+        let mut buf = [0; MAX_LEN];
+        let len = encode(value, &mut buf);
+        assert_eq!(len, expected_len);
+        assert_eq!(encoded_len(value), expected_len);
+        assert_eq!(decode(&buf[..len]), Ok((value, len)));
+    }
+
+    #[test]
+    fn lengths() {
+        round_trip::<{ u64::MAX }>(None, 1);
+        round_trip::<{ u64::MAX }>(Some(0), 1);
+        round_trip::<{ u64::MAX }>(Some(126), 1);
+        round_trip::<{ u64::MAX }>(Some(127), 2);
+        round_trip::<{ u64::MAX }>(Some(u64::MAX - 1), MAX_LEN);
+        round_trip::<0>(None, 1);
+        round_trip::<0>(Some(127), 1);
+        round_trip::<0>(Some(u64::MAX), MAX_LEN);
+    }
+
+    #[test]
+    fn none_is_zero() {
+        let mut buf = [0xff; MAX_LEN];
+        assert_eq!(encode(IntSentinel::new_none(), &mut buf), 1);
+        assert_eq!(buf[0], 0);
+        assert_eq!(encode(IntSentinelWith::<42>::new_none(), &mut buf), 1);
+        assert_eq!(buf[0], 0);
+    }
+
+    #[test]
+    fn truncated() {
+        assert_eq!(decode::<{ u64::MAX }>(&[]), Err(VarintError::Truncated));
+        assert_eq!(decode::<{ u64::MAX }>(&[0xff, 0xff]), Err(VarintError::Truncated));
+    }
+
+    #[test]
+    fn overlong() {
+        assert_eq!(decode::<{ u64::MAX }>(&[0x81, 0x00]), Err(VarintError::Overlong));
+        assert_eq!(decode::<{ u64::MAX }>(&[0xff; 11]), Err(VarintError::Overlong));
+        let mut buf = [0xff; MAX_LEN];
+        buf[MAX_LEN - 1] = 0x02;
+        assert_eq!(decode::<{ u64::MAX }>(&buf), Err(VarintError::Overlong));
+        buf[MAX_LEN - 1] = 0x01;
+        assert_eq!(decode::<{ u64::MAX }>(&buf).unwrap().0, IntSentinel::from(Some(u64::MAX - 1)));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn io_errors() {
+        let error = read::<_, { u64::MAX }>(&mut &[0xff][..]).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::UnexpectedEof);
+        let error = read::<_, { u64::MAX }>(&mut &[0x80, 0x00][..]).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn streaming() {
+        let values: Vec<IntSentinel> = vec![None.into(), Some(1 << 40).into(), Some(3).into()];
+        let mut buf = Vec::new();
+        for &value in &values {
+            write(&mut buf, value).unwrap();
+        }
+        let mut reader = &buf[..];
+        for &value in &values {
+            assert_eq!(read(&mut reader).unwrap(), value);
+        }
+        assert!(reader.is_empty());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn slices() {
+        let values: Vec<IntSentinel> = (0..200).map(|x| if x % 5 == 0 { None } else { Some(x * x * x) }.into()).collect();
+        let mut buf = Vec::new();
+        encode_slice(&values, &mut buf);
+        let mut decoded = Vec::new();
+        decode_slice(&buf, &mut decoded).unwrap();
+        assert_eq!(decoded, values);
+        buf.push(0x80);
+        let mut decoded: Vec<IntSentinel> = Vec::new();
+        assert_eq!(decode_slice(&buf, &mut decoded), Err(VarintError::Truncated));
+        assert_eq!(decoded, values);
+    }
+}
@@ -0,0 +1,77 @@
+//! Exercises the core API from a `no_std` crate.
+//!
+//! Run with `cargo test --no-default-features --test no_std` to check that the library builds without `std`.
+
+#![no_std]
+
+extern crate sentinel_int;
+
+use core::convert::TryFrom;
+use core::fmt::Write;
+
+use sentinel_int::bulk;
+use sentinel_int::int_sentinel::{IntSentinel, IntSentinelI32, IntSentinelU8};
+use sentinel_int::ordering::{NoneLast, OrderingPolicy, TotalOrderingPolicy};
+use sentinel_int::sentinel::Sentinelled;
+use sentinel_int::varint;
+
+/// A fixed-size `fmt::Write` sink, since `String` is not available.
+struct Buf {
+    bytes: [u8; 32],
+    len: usize,
+}
+
+impl Buf {
+    fn new() -> Self {
+        Buf { bytes: [0; 32], len: 0 }
+    }
+
+    fn as_str(&self) -> &str {
+        core::str::from_utf8(&self.bytes[..self.len]).unwrap()
+    }
+}
+
+impl Write for Buf {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        let end = self.len + s.len();
+        self.bytes[self.len..end].copy_from_slice(s.as_bytes());
+        self.len = end;
+        Ok(())
+    }
+}
+
+#[test]
+fn conversions() {
+    let some = IntSentinel::from(Some(42));
+    assert_eq!(some.to_option(), Some(42));
+    assert!(IntSentinel::try_new_with_some(u64::MAX).is_err());
+    assert_eq!(IntSentinelU8::try_from(3).unwrap().to_option(), Some(3));
+    assert_eq!(IntSentinelI32::NONE.to_option(), None);
+    assert_eq!(Sentinelled::<i64>::from(Some(-1)).to_option(), Some(-1));
+}
+
+#[test]
+fn text() {
+    let sentinel: IntSentinel = "0x2a".parse().unwrap();
+    let mut buf = Buf::new();
+    write!(buf, "{} {}", sentinel, IntSentinel::NONE.display_with("NA")).unwrap();
+    assert_eq!(buf.as_str(), "42 NA");
+}
+
+#[test]
+fn slices() {
+    let options = [Some(3), None, Some(1)];
+    let mut sentinels: [IntSentinel; 3] = Default::default();
+    bulk::encode_slice(&options, &mut sentinels);
+    NoneLast::sort_unstable(&mut sentinels);
+    assert!(NoneLast::try_sort(&mut sentinels).is_ok());
+    assert_eq!(IntSentinel::as_raw_slice(&sentinels), &[1, 3, u64::MAX]);
+}
+
+#[test]
+fn varint() {
+    let mut buf = [0; varint::MAX_LEN];
+    let len = varint::encode(IntSentinel::NONE, &mut buf);
+    assert_eq!(&buf[..len], &[0]);
+    assert_eq!(varint::decode(&buf[..len]), Ok((IntSentinel::NONE, 1)));
+}